@@ -98,6 +98,36 @@
 //! /deck code:CIBQCAICAQAQGBQIBEBAMBAJBMGBUHJNGE4AEAIBAIYQEAQGEU2QCAIBAIUQ name:Gimbo's Depths
 //! ```
 //!
+//! ### Deck comparison
+//!
+//! You can have [Patched Porobot#7556] display the cards added and removed between two decks by specifying the `/diff` command:
+//!
+//! ```text
+//! /diff before:CIBQCAICAQAQGBQIBEBAMBAJBMGBUHJNGE4AEAIBAIYQEAQGEU2QCAIBAIUQ after:CQBQCBAJBUCAKCRYHKTADNIBAYBQSDQ2DQ3FEWACAECQVNQBAIBQSOK5AEAQGCIV
+//! ```
+//!
+//! ### Draw probabilities
+//!
+//! You can ask [Patched Porobot#7556] for the chance to draw at least one of the cards of a deck matching a search query, by specifying the `/draw` command:
+//!
+//! ```text
+//! /draw code:CIBQCAICAQAQGBQIBEBAMBAJBMGBUHJNGE4AEAIBAIYQEAQGEU2QCAIBAIUQ card:name:Bard round:4
+//! ```
+//!
+//! Chances are calculated both for keeping the whole opening hand, and for mulliganing away every card not matching the query.
+//!
+//! ### Random decks
+//!
+//! You can have [Patched Porobot#7556] generate a random deck legal in a format by specifying the `/random` command, optionally with the name of the format and the seed of the generation:
+//!
+//! ```text
+//! /random format:Singleton seed:42
+//! ```
+//!
+//! ### Languages
+//!
+//! Cards are searched and displayed in the language of your Discord client, if it is one of the languages loaded by the bot; otherwise, the default language of the bot is used.
+//!
 //! ### Permissions
 //!
 //! You can configure the bot's permissions by using Discord's Command Permissions system!
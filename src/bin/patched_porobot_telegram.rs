@@ -82,6 +82,8 @@
 //!
 //! Then, select the "Deck with N cards" option to send the deck's card list in the chat!
 //!
+//! When possible, the option is titled with the archetype of the deck instead, such as _Ezreal Draven (PZ/NX) deck with N cards_.
+//!
 //! #### Named decks
 //!
 //! Optionally, you may add a name to your deck, which will be displayed above the deck code:
@@ -92,6 +94,20 @@
 //!
 //! If entered correctly, the bot will display a slightly different option containing the deck's name (_Deck "NAME" with N cards_), which you can check before the message is sent to the chat.
 //!
+//! ### Deck comparison
+//!
+//! You can have [@patchedporobot] display the cards added and removed between two decks by pasting both deck codes, separated by a space:
+//!
+//! ```text
+//! @patchedporobot CIBQCAICAQAQGBQIBEBAMBAJBMGBUHJNGE4AEAIBAIYQEAQGEU2QCAIBAIUQ CQBQCBAJBUCAKCRYHKTADNIBAYBQSDQ2DQ3FEWACAECQVNQBAIBQSOK5AEAQGCIV
+//! ```
+//!
+//! Then, select the "Difference between decks" option to send the list of changes in the chat!
+//!
+//! ### Languages
+//!
+//! Cards are searched and displayed in the language of your Telegram app, if it is one of the languages loaded by the bot; otherwise, the default language of the bot is used.
+//!
 //! [@patchedporobot]: https://t.me/patchedporobot
 
 #![doc(html_logo_url = "https://raw.githubusercontent.com/Steffo99/patched-porobot/main/icon.png")]
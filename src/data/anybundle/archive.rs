@@ -0,0 +1,148 @@
+//! Module defining [BundleArchive], which reads the files of a [Data Dragon] Bundle from a zip archive.
+//!
+//! [Data Dragon]: https://developer.riotgames.com/docs/lor#data-dragon
+
+use crate::data::anybundle::metadata::BundleMetadata;
+use crate::data::anybundle::outcomes::{LoadingError, LoadingResult};
+use itertools::Itertools;
+use serde::de::DeserializeOwned;
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::Path;
+use zip::ZipArchive;
+
+/// Whether the given path should be loaded as a [BundleArchive], because it has the `.zip` extension, instead of as a bundle directory.
+pub fn is_archive(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("zip"))
+}
+
+/// A Data Dragon Bundle packed in a zip archive, such as the `set1-lite-en_us.zip` files distributed by Riot Games.
+///
+/// The files of the bundle may be either at the root of the archive, or inside a single directory.
+pub struct BundleArchive<R: Read + Seek> {
+    /// The opened archive.
+    archive: ZipArchive<R>,
+
+    /// The path of the directory containing the `metadata.json` file inside the archive, ending with a slash if not empty.
+    prefix: String,
+}
+
+impl BundleArchive<File> {
+    /// Open the zip archive at the given path.
+    pub fn open(path: &Path) -> LoadingResult<Self> {
+        let file = File::open(path).map_err(LoadingError::OpeningFile)?;
+        Self::new(file)
+    }
+}
+
+impl<R: Read + Seek> BundleArchive<R> {
+    /// Read a zip archive from the given reader, such as a [std::io::Cursor] over an in-memory buffer.
+    pub fn new(reader: R) -> LoadingResult<Self> {
+        let archive = ZipArchive::new(reader).map_err(LoadingError::ReadingArchive)?;
+
+        let prefix = archive
+            .file_names()
+            .filter(|name| *name == "metadata.json" || name.ends_with("/metadata.json"))
+            .min_by_key(|name| name.matches('/').count())
+            .map(|name| name.trim_end_matches("metadata.json").to_string())
+            .ok_or(LoadingError::ReadingArchive(zip::result::ZipError::FileNotFound))?;
+
+        Ok(Self { archive, prefix })
+    }
+
+    /// Read and deserialize the JSON file at the given path, relative to the root of the bundle.
+    pub fn read_json<T: DeserializeOwned>(&mut self, path: &str) -> LoadingResult<T> {
+        let file = self.archive
+            .by_name(&format!("{}{}", self.prefix, path))
+            .map_err(LoadingError::ReadingArchive)?;
+
+        serde_json::de::from_reader::<_, T>(file).map_err(LoadingError::Deserializing)
+    }
+
+    /// Read the `metadata.json` file of the bundle.
+    pub fn metadata(&mut self) -> LoadingResult<BundleMetadata> {
+        self.read_json("metadata.json")
+    }
+
+    /// List the paths of the JSON files in the `[locale]/data` directory of the bundle, relative to the root of the bundle.
+    pub fn data_files(&self, locale: &str) -> Vec<String> {
+        let directory = format!("{}{}/data/", self.prefix, locale);
+
+        self.archive
+            .file_names()
+            .filter_map(|name| name.strip_prefix(&self.prefix).filter(|_| name.starts_with(&directory)))
+            .filter(|name| name.ends_with(".json"))
+            .map(String::from)
+            .sorted()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::corebundle::CoreBundle;
+    use crate::data::setbundle::SetBundle;
+    use std::io::{Cursor, Write};
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    /// Pack the `metadata.json` and data file of a bundle in the `data` directory into an in-memory zip archive, inside the given directory.
+    fn pack(bundle: &str, data: &str, prefix: &str) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = FileOptions::default();
+
+        for file in ["metadata.json", data] {
+            let contents = std::fs::read(Path::new("./data").join(bundle).join(file)).unwrap();
+            writer.start_file(format!("{}{}", prefix, file), options).unwrap();
+            writer.write_all(&contents).unwrap();
+        }
+        writer.start_file(format!("{}en_us/img/cards/01FR009.png", prefix), options).unwrap();
+
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn load_set_zip() {
+        let expected = SetBundle::load(Path::new("./data/set1-en_us")).unwrap();
+
+        let archive = pack("set1-en_us", "en_us/data/set1-en_us.json", "");
+        assert_eq!(SetBundle::from_zip(Cursor::new(archive)).unwrap(), expected);
+
+        let archive = pack("set1-en_us", "en_us/data/set1-en_us.json", "set1-lite-en_us/");
+        assert_eq!(SetBundle::from_zip(Cursor::new(archive.clone())).unwrap(), expected);
+
+        let path = std::env::temp_dir().join(format!("patched_porobot_set1-lite-en_us_{}.zip", std::process::id()));
+        std::fs::write(&path, archive).unwrap();
+        assert_eq!(SetBundle::load_any(&path).unwrap(), expected);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn load_core_zip() {
+        let expected = CoreBundle::load(Path::new("./data/core-en_us")).unwrap();
+
+        let archive = pack("core-en_us", "en_us/data/globals-en_us.json", "");
+        assert_eq!(CoreBundle::from_zip(Cursor::new(archive)).unwrap(), expected);
+    }
+
+    #[test]
+    fn archive_extension() {
+        assert!(is_archive(Path::new("./data/set1-lite-en_us.zip")));
+        assert!(is_archive(Path::new("./data/CORE-EN_US.ZIP")));
+        assert!(!is_archive(Path::new("./data/set1-en_us")));
+    }
+
+    #[test]
+    fn invalid_archives() {
+        assert!(matches!(SetBundle::from_zip(Cursor::new(b"not a zip".to_vec())), Err(LoadingError::ReadingArchive(_))));
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer.start_file("README.md", FileOptions::default()).unwrap();
+        let archive = writer.finish().unwrap().into_inner();
+        assert!(matches!(CoreBundle::from_zip(Cursor::new(archive)), Err(LoadingError::ReadingArchive(_))));
+
+        let archive = pack("core-en_us", "en_us/data/globals-en_us.json", "");
+        assert!(matches!(SetBundle::from_zip(Cursor::new(archive)), Err(LoadingError::Deserializing(_))));
+    }
+}
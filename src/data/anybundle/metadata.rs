@@ -38,9 +38,9 @@ impl BundleMetadata {
 
     /// Get a reference to the first (and probably only) locale defined in BundleMetadata.
     ///
-    /// Equivalent to calling [BundleMetadata].[locales](BundleMetadata::locales).[get(0)]([T]::get).
+    /// Equivalent to calling [BundleMetadata].[locales](BundleMetadata::locales).[first()]([T]::first).
     pub fn locale(&self) -> Option<&String> {
-        self.locales.get(0)
+        self.locales.first()
     }
 }
 
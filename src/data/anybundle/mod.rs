@@ -2,5 +2,6 @@
 //!
 //! [Data Dragon]: https://developer.riotgames.com/docs/lor#data-dragon
 
+pub mod archive;
 pub mod metadata;
 pub mod outcomes;
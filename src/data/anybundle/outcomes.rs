@@ -2,6 +2,8 @@
 //!
 //! [Data Dragon]: https://developer.riotgames.com/docs/lor#data-dragon
 
+use std::fmt::{Display, Formatter};
+
 /// An error that occoured while loading a Data Dragon Bundle.
 #[derive(Debug)]
 pub enum LoadingError {
@@ -9,15 +11,82 @@ pub enum LoadingError {
     GettingLocale,
     /// Could not get the bundle name from the operating system.
     GettingBundleName,
+    /// Could not find any bundle to load.
+    NoBundleFound,
     /// Could not use [File::open](std::fs::File::open) on a data file.
     OpeningFile(std::io::Error),
+    /// Could not read a zip archive, or find a data file inside it.
+    ReadingArchive(zip::result::ZipError),
+    /// Could not write a data file or directory to disk.
+    WritingFile(std::io::Error),
     /// Could not deserialize a data file.
     Deserializing(serde_json::Error),
     /// Could not fetch a data file from a remote location.
     RemoteFetching(reqwest::Error),
     /// Could not deserialize a data file from a remote location.
     RemoteDeserializing(reqwest::Error),
+    /// The given Data Dragon version is not `latest` nor a numbered version such as `5_9_0`.
+    InvalidVersion(String),
+}
+
+impl Display for LoadingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadingError::GettingLocale => write!(f, "the locale of the bundle could not be determined"),
+            LoadingError::GettingBundleName => write!(f, "the name of the bundle could not be determined"),
+            LoadingError::NoBundleFound => write!(f, "no bundle could be found"),
+            LoadingError::OpeningFile(e) => write!(f, "a data file could not be opened: {}", e),
+            LoadingError::ReadingArchive(e) => write!(f, "the archive could not be read: {}", e),
+            LoadingError::WritingFile(e) => write!(f, "a data file could not be written: {}", e),
+            LoadingError::Deserializing(e) => write!(f, "a data file could not be deserialized: {}", e),
+            LoadingError::RemoteFetching(e) => write!(f, "a data file could not be fetched: {}", e),
+            LoadingError::RemoteDeserializing(e) => write!(f, "a fetched data file could not be deserialized: {}", e),
+            LoadingError::InvalidVersion(v) => write!(f, "{:?} is not a valid Data Dragon version", v),
+        }
+    }
 }
 
 /// The result of the loading of a Legends of Runeterra bundle.
 pub type LoadingResult<T> = Result<T, LoadingError>;
+
+/// A bundle which could not be loaded, while loading multiple bundles together.
+#[derive(Debug)]
+pub struct BundleFailure {
+    /// The bundle which could not be loaded, identified by its path or by its set code.
+    pub bundle: String,
+    /// Why the bundle could not be loaded.
+    pub error: LoadingError,
+}
+
+impl Display for BundleFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.bundle, self.error)
+    }
+}
+
+/// The result of loading multiple bundles together, failing if any of them could not be loaded.
+pub type BundlesResult<T> = Result<T, Vec<BundleFailure>>;
+
+/// The outcome of loading multiple bundles together, skipping the ones which could not be loaded.
+#[derive(Debug)]
+pub struct PartialLoad<T> {
+    /// The data of the bundles which were loaded successfully.
+    pub value: T,
+    /// The bundles which could not be loaded.
+    pub failures: Vec<BundleFailure>,
+}
+
+impl<T> PartialLoad<T> {
+    /// Whether every bundle was loaded successfully.
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Get the loaded data if every bundle was loaded successfully, or the list of failures otherwise.
+    pub fn into_result(self) -> BundlesResult<T> {
+        match self.failures.is_empty() {
+            true => Ok(self.value),
+            false => Err(self.failures),
+        }
+    }
+}
@@ -0,0 +1,392 @@
+//! Module defining [`BundleCache`], a local copy of [Data Dragon] bundles which avoids downloading them again at every start.
+//!
+//! Bundles are stored with the same layout used by the bundles distributed by Riot Games, so that they can be read by [`SetBundle::load`] and [`CoreBundle::load`]:
+//!
+//! ```text
+//! [root]/[version]/core-[locale]/metadata.json
+//! [root]/[version]/core-[locale]/[locale]/data/globals-[locale].json
+//! [root]/[version]/[set]-[locale]/metadata.json
+//! [root]/[version]/[set]-[locale]/[locale]/data/[set]-[locale].json
+//! ```
+//!
+//! [Data Dragon]: https://developer.riotgames.com/docs/lor#data-dragon
+
+use crate::data::anybundle::metadata::BundleMetadata;
+use crate::data::anybundle::outcomes::{BundleFailure, LoadingError, LoadingResult, PartialLoad};
+use crate::data::corebundle::globals::{LocalizedGlobalsIndexes, LocalizedGlobalsVecs};
+use crate::data::corebundle::CoreBundle;
+use crate::data::setbundle::card::{Card, CardIndex};
+use crate::data::setbundle::{create_cardindex_from_paths_partial, SetBundle};
+use itertools::Itertools;
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The base URL of Data Dragon, to which the version is appended.
+pub const DATA_DRAGON_URL: &str = "https://dd.b.pvp.net";
+
+/// A directory containing local copies of [Data Dragon] bundles, grouped by version.
+///
+/// Versions are the names Data Dragon uses for them, such as `5_9_0` or `latest`; any other version is rejected with [`LoadingError::InvalidVersion`], so that it can never point outside of the cache.
+/// Note that `latest` is cached like any other version, so it will not follow new patches until it is evicted.
+///
+/// [Data Dragon]: https://developer.riotgames.com/docs/lor#data-dragon
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BundleCache {
+    /// The directory containing the cached versions.
+    pub root: PathBuf,
+}
+
+impl BundleCache {
+    /// Use the given directory as cache; it is created when the first bundle is stored.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// The directory containing the bundles of the given version.
+    pub fn version_path(&self, version: &str) -> LoadingResult<PathBuf> {
+        match is_valid_version(version) {
+            true => Ok(self.root.join(version)),
+            false => Err(LoadingError::InvalidVersion(version.to_string())),
+        }
+    }
+
+    /// The directory of the Core Bundle of the given version and locale.
+    pub fn core_path(&self, version: &str, locale: &str) -> LoadingResult<PathBuf> {
+        Ok(self.version_path(version)?.join(format!("core-{}", locale)))
+    }
+
+    /// The directory of the Set Bundle of the given version, locale and set.
+    pub fn set_path(&self, version: &str, locale: &str, set: &str) -> LoadingResult<PathBuf> {
+        Ok(self.version_path(version)?.join(format!("{}-{}", set, locale)))
+    }
+
+    /// Whether the Core Bundle and all the given Set Bundles of the given version and locale are cached.
+    ///
+    /// Invalid versions are never cached.
+    pub fn is_cached<'s>(&self, version: &str, locale: &str, sets: impl IntoIterator<Item = &'s str>) -> bool {
+        let is_bundle_cached = |path: LoadingResult<PathBuf>| path.is_ok_and(|path| path.join("metadata.json").is_file());
+
+        is_bundle_cached(self.core_path(version, locale))
+            && sets
+                .into_iter()
+                .all(|set| is_bundle_cached(self.set_path(version, locale, set)))
+    }
+
+    /// Download from Data Dragon the Core Bundle and the given Set Bundles of the given version and locale which are not cached yet.
+    ///
+    /// Every downloaded bundle is checked to be deserializable before it is stored.
+    pub async fn fetch<'s>(&self, client: &reqwest::Client, version: &str, locale: &str, sets: impl IntoIterator<Item = &'s str>) -> LoadingResult<()> {
+        let base_url = version_url(version)?;
+
+        let core_path = self.core_path(version, locale)?;
+        if !core_path.join("metadata.json").is_file() {
+            let url = format!("{}/core/{}/data/globals-{}.json", base_url, locale, locale);
+            let data = Self::download(client, &url).await?;
+            serde_json::from_str::<LocalizedGlobalsVecs>(&data).map_err(LoadingError::Deserializing)?;
+            Self::store(&core_path, locale, &format!("globals-{}.json", locale), &data)?;
+        }
+
+        for set in sets {
+            let set_path = self.set_path(version, locale, set)?;
+            if set_path.join("metadata.json").is_file() {
+                continue;
+            }
+
+            let url = format!("{}/{}/{}/data/{}-{}.json", base_url, set, locale, set, locale);
+            let data = Self::download(client, &url).await?;
+            serde_json::from_str::<Vec<Card>>(&data).map_err(LoadingError::Deserializing)?;
+            Self::store(&set_path, locale, &format!("{}-{}.json", set, locale), &data)?;
+        }
+
+        Ok(())
+    }
+
+    /// Download the contents of a data file.
+    async fn download(client: &reqwest::Client, url: &str) -> LoadingResult<String> {
+        log::debug!("Downloading bundle data from {} ...", url);
+
+        client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(LoadingError::RemoteFetching)?
+            .text()
+            .await
+            .map_err(LoadingError::RemoteFetching)
+    }
+
+    /// Store a bundle with a single data file in the given directory.
+    ///
+    /// The bundle is written to a temporary directory first, so that an interrupted write never leaves a bundle which looks complete.
+    fn store(bundle_path: &Path, locale: &str, filename: &str, data: &str) -> LoadingResult<()> {
+        let mut partial_name = bundle_path.file_name().ok_or(LoadingError::GettingBundleName)?.to_os_string();
+        partial_name.push(".partial");
+        let partial_path = bundle_path.with_file_name(partial_name);
+
+        if partial_path.exists() {
+            fs::remove_dir_all(&partial_path).map_err(LoadingError::WritingFile)?;
+        }
+
+        let data_path = partial_path.join(locale).join("data");
+        fs::create_dir_all(&data_path).map_err(LoadingError::WritingFile)?;
+        fs::write(data_path.join(filename), data).map_err(LoadingError::WritingFile)?;
+
+        let metadata = BundleMetadata { locales: vec![locale.to_string()] };
+        let metadata = serde_json::to_string(&metadata).expect("BundleMetadata to be serializable");
+        fs::write(partial_path.join("metadata.json"), metadata).map_err(LoadingError::WritingFile)?;
+
+        fs::rename(&partial_path, bundle_path).map_err(LoadingError::WritingFile)
+    }
+
+    /// Load the [`LocalizedGlobalsIndexes`] of the given version and locale from the cache.
+    pub fn load_globals(&self, version: &str, locale: &str) -> LoadingResult<LocalizedGlobalsIndexes> {
+        let core = CoreBundle::load(&self.core_path(version, locale)?)?;
+        Ok(LocalizedGlobalsIndexes::from(core.globals))
+    }
+
+    /// Load a [`CardIndex`] containing the given sets of the given version and locale from the cache.
+    pub fn load_cards<'s>(&self, version: &str, locale: &str, sets: impl IntoIterator<Item = &'s str>) -> LoadingResult<CardIndex> {
+        let mut index = CardIndex::new();
+
+        for set in sets {
+            let bundle = SetBundle::load(&self.set_path(version, locale, set)?)?;
+            for card in bundle.cards {
+                index.insert(card.code.clone(), card);
+            }
+        }
+
+        Ok(index)
+    }
+
+    /// Load a [`CardIndex`] containing the given sets of the given version and locale from the cache, skipping the sets which cannot be loaded.
+    ///
+    /// If the version is invalid, every set is reported as a failure.
+    pub fn load_cards_partial<'s>(&self, version: &str, locale: &str, sets: impl IntoIterator<Item = &'s str>) -> PartialLoad<CardIndex> {
+        let mut paths = vec![];
+        let mut failures = vec![];
+
+        for set in sets {
+            match self.set_path(version, locale, set) {
+                Ok(path) => paths.push(path),
+                Err(error) => failures.push(BundleFailure { bundle: set.to_string(), error }),
+            }
+        }
+
+        let mut partial = create_cardindex_from_paths_partial(paths.into_iter());
+        partial.failures.extend(failures);
+        partial
+    }
+
+    /// List the cached versions, from the oldest to the newest.
+    ///
+    /// Numbered versions are sorted numerically, and are considered older than named versions such as `latest`; directories which are not named after a valid version are ignored.
+    pub fn versions(&self) -> LoadingResult<Vec<String>> {
+        if !self.root.exists() {
+            return Ok(vec![]);
+        }
+
+        let versions = fs::read_dir(&self.root)
+            .map_err(LoadingError::OpeningFile)?
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|version| is_valid_version(version))
+            .sorted_by(|a, b| compare_versions(a, b))
+            .collect();
+
+        Ok(versions)
+    }
+
+    /// Remove all the bundles of the given version from the cache, returning whether the version was cached.
+    pub fn evict(&self, version: &str) -> LoadingResult<bool> {
+        let path = self.version_path(version)?;
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        log::debug!("Evicting cached bundles of version {} ...", version);
+        fs::remove_dir_all(path).map_err(LoadingError::WritingFile)?;
+        Ok(true)
+    }
+
+    /// Remove all the versions from the cache except the `keep` newest ones, returning the evicted versions.
+    pub fn evict_old(&self, keep: usize) -> LoadingResult<Vec<String>> {
+        let versions = self.versions()?;
+        let count = versions.len().saturating_sub(keep);
+        let evicted = versions.into_iter().take(count).collect_vec();
+
+        for version in evicted.iter() {
+            self.evict(version)?;
+        }
+
+        Ok(evicted)
+    }
+}
+
+/// Check whether the given string is a valid Data Dragon version, either `latest` or a numbered version such as `5_9_0`.
+pub fn is_valid_version(version: &str) -> bool {
+    version == "latest" || {
+        let parts = version.split('_').collect_vec();
+        parts.len() == 3 && parts.iter().all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+    }
+}
+
+/// Get the base URL of the given version of Data Dragon, such as `https://dd.b.pvp.net/5_9_0`, failing if the version [is not valid](is_valid_version).
+pub fn version_url(version: &str) -> LoadingResult<String> {
+    match is_valid_version(version) {
+        true => Ok(format!("{}/{}", DATA_DRAGON_URL, version)),
+        false => Err(LoadingError::InvalidVersion(version.to_string())),
+    }
+}
+
+/// Compare two Data Dragon versions, such as `5_9_0` and `5_10_0`.
+///
+/// Numbered versions are compared component by component; named versions come after all numbered ones.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |version: &str| -> Option<Vec<u32>> {
+        version.split('_').map(|part| part.parse::<u32>().ok()).collect()
+    };
+
+    match (parse(a), parse(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+    }
+}
+
+/// Create [`LocalizedGlobalsIndexes`] and a [`CardIndex`] from the given [`BundleCache`], downloading from Data Dragon the bundles which are not cached yet.
+///
+/// Sets which cannot be fetched nor loaded are skipped, and reported in the returned [`PartialLoad`]; fails if the Core Bundle is not cached and cannot be fetched, or if the cached Core Bundle cannot be loaded.
+pub async fn create_indexes_from_cache(cache: &BundleCache, version: &str, locale: &str, known_set_codes: &[&str]) -> LoadingResult<(LocalizedGlobalsIndexes, PartialLoad<CardIndex>)> {
+    let client = reqwest::Client::new();
+
+    if !cache.is_cached(version, locale, []) {
+        cache.fetch(&client, version, locale, []).await?;
+    }
+
+    for set in known_set_codes {
+        if let Err(error) = cache.fetch(&client, version, locale, [*set]).await {
+            log::warn!("Could not fetch SetBundle {} to cache: {}", set, error);
+        }
+    }
+
+    let globals = cache.load_globals(version, locale)?;
+    let cards = cache.load_cards_partial(version, locale, known_set_codes.iter().copied());
+
+    Ok((globals, cards))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::env;
+
+    /// Create an empty [`BundleCache`] in a temporary directory unique to the test.
+    pub(crate) fn temporary_cache(name: &str) -> BundleCache {
+        let root = env::temp_dir().join(format!("patched_porobot_cache_{}_{}", name, std::process::id()));
+        if root.exists() {
+            fs::remove_dir_all(&root).unwrap();
+        }
+        BundleCache::new(root)
+    }
+
+    /// Copy a bundle from the `data` directory of the working directory into the cache.
+    pub(crate) fn copy_bundle(cache: &BundleCache, version: &str, name: &str) {
+        let source = PathBuf::from("./data").join(name);
+        let target = cache.version_path(version).unwrap().join(name);
+        let data = format!("en_us/data/{}.json", name.replace("core-", "globals-"));
+
+        fs::create_dir_all(target.join("en_us/data")).unwrap();
+        fs::copy(source.join("metadata.json"), target.join("metadata.json")).unwrap();
+        fs::copy(source.join(&data), target.join(&data)).unwrap();
+    }
+
+    #[test]
+    fn load_cached() {
+        let cache = temporary_cache("load");
+        copy_bundle(&cache, "5_9_0", "core-en_us");
+        copy_bundle(&cache, "5_9_0", "set1-en_us");
+
+        assert!(cache.is_cached("5_9_0", "en_us", ["set1"]));
+        assert!(!cache.is_cached("5_9_0", "en_us", ["set1", "set2"]));
+        assert!(!cache.is_cached("latest", "en_us", []));
+
+        let globals = cache.load_globals("5_9_0", "en_us").unwrap();
+        assert!(!globals.regions.is_empty());
+        let cards = cache.load_cards("5_9_0", "en_us", ["set1"]).unwrap();
+        assert!(cards.keys().all(|code| code.full.starts_with("01")));
+
+        let partial = cache.load_cards_partial("5_9_0", "en_us", ["set1", "set2"]);
+        assert_eq!(partial.value, cards);
+        assert_eq!(partial.failures.len(), 1);
+        assert!(partial.failures[0].bundle.ends_with("set2-en_us"));
+
+        fs::remove_dir_all(&cache.root).unwrap();
+    }
+
+    #[test]
+    fn list_and_evict() {
+        let cache = temporary_cache("evict");
+        assert_eq!(cache.versions().unwrap(), Vec::<String>::new());
+
+        for version in ["latest", "5_10_0", "4_2_0", "5_9_0"] {
+            fs::create_dir_all(cache.version_path(version).unwrap()).unwrap();
+        }
+        assert_eq!(cache.versions().unwrap(), vec!["4_2_0", "5_9_0", "5_10_0", "latest"]);
+
+        assert!(cache.evict("latest").unwrap());
+        assert!(!cache.evict("latest").unwrap());
+        assert_eq!(cache.evict_old(1).unwrap(), vec!["4_2_0", "5_9_0"]);
+        assert_eq!(cache.versions().unwrap(), vec!["5_10_0"]);
+
+        fs::remove_dir_all(&cache.root).unwrap();
+    }
+
+    #[test]
+    fn reject_invalid_versions() {
+        let cache = temporary_cache("invalid");
+        fs::create_dir_all(cache.root.join("not_a_version")).unwrap();
+        assert_eq!(cache.versions().unwrap(), Vec::<String>::new());
+
+        for version in ["", ".", "..", "../5_9_0", "5_9_0/..", "5_9", "5_9_0_1", "5__0", "Latest"] {
+            assert!(matches!(cache.evict(version), Err(LoadingError::InvalidVersion(_))));
+            assert!(!cache.is_cached(version, "en_us", []));
+            assert!(matches!(version_url(version), Err(LoadingError::InvalidVersion(_))));
+        }
+        assert!(cache.root.exists());
+        assert_eq!(version_url("5_9_0").unwrap(), "https://dd.b.pvp.net/5_9_0");
+
+        let partial = cache.load_cards_partial("..", "en_us", ["set1"]);
+        assert!(partial.value.is_empty());
+        assert_eq!(partial.failures[0].bundle, "set1");
+
+        fs::remove_dir_all(&cache.root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn corrupted_core_bundle() {
+        let cache = temporary_cache("corrupted");
+        copy_bundle(&cache, "5_9_0", "core-en_us");
+        fs::write(cache.core_path("5_9_0", "en_us").unwrap().join("en_us/data/globals-en_us.json"), "{").unwrap();
+
+        let result = create_indexes_from_cache(&cache, "5_9_0", "en_us", &[]).await;
+        assert!(matches!(result, Err(LoadingError::Deserializing(_))));
+
+        fs::remove_dir_all(&cache.root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn fetch_into_cache() {
+        let cache = temporary_cache("fetch");
+        let client = reqwest::Client::new();
+
+        cache.fetch(&client, "5_9_0", "en_us", ["set1"]).await.unwrap();
+        assert!(cache.is_cached("5_9_0", "en_us", ["set1"]));
+        assert!(!cache.load_cards("5_9_0", "en_us", ["set1"]).unwrap().is_empty());
+
+        fs::remove_dir_all(&cache.root).unwrap();
+    }
+}
@@ -3,7 +3,8 @@
 use super::keyword::{LocalizedCardKeywordIndex, LocalizedCardKeywordVec};
 use super::rarity::{LocalizedCardRarityIndex, LocalizedCardRarityVec};
 use super::region::{LocalizedCardRegionIndex, LocalizedCardRegionVec};
-use super::set::{LocalizedCardSetIndex, LocalizedCardSetVec};
+use super::set::{LocalizedCardSetIndex, LocalizedCardSetVec, LocalizedUnsupportedCardSetIndex};
+use crate::data::setbundle::set::CardSet;
 use super::speed::{LocalizedSpellSpeedIndex, LocalizedSpellSpeedVec};
 use super::vocabterm::{LocalizedVocabTermIndex, LocalizedVocabTermVec};
 use super::format::{LocalizedCardFormatIndex, LocalizedCardFormatVec};
@@ -71,6 +72,9 @@ pub struct LocalizedGlobalsIndexes {
     /// Card sets.
     pub sets: LocalizedCardSetIndex,
 
+    /// Card sets unknown to this version of the crate.
+    pub unsupported_sets: LocalizedUnsupportedCardSetIndex,
+
     /// Card formats.
     pub formats: LocalizedCardFormatIndex,
 }
@@ -125,8 +129,17 @@ impl From<LocalizedGlobalsVecs> for LocalizedGlobalsIndexes {
             },
             sets: {
                 let mut hm = LocalizedCardSetIndex::new();
-                for obj in o.sets {
-                    hm.insert(obj.set, obj);
+                for obj in o.sets.iter().filter(|obj| obj.set != CardSet::Unsupported) {
+                    hm.insert(obj.set, obj.clone());
+                }
+                hm
+            },
+            unsupported_sets: {
+                let mut hm = LocalizedUnsupportedCardSetIndex::new();
+                for obj in o.sets.into_iter().filter(|obj| obj.set == CardSet::Unsupported) {
+                    if let Some(id) = obj.id() {
+                        hm.insert(id, obj);
+                    }
                 }
                 hm
             },
@@ -255,6 +268,7 @@ mod tests {
                 sets: vec![
                     LocalizedCardSet {
                         set: CardSet::CallOfTheMountain,
+                        name_ref: "Set3".to_string(),
                         name: "Call of the Mountain".to_string(),
                         icon_png: "http://dd.b.pvp.net/3_11_0/core/en_us/img/sets/set3_crispmip.png".to_string()
                     }
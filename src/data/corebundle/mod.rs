@@ -3,8 +3,11 @@
 //! [Data Dragon]: https://developer.riotgames.com/docs/lor#data-dragon
 //! [Core Bundle]: https://developer.riotgames.com/docs/lor#data-dragon_core-bundles
 
+use super::anybundle::archive::{is_archive, BundleArchive};
 use super::anybundle::metadata::BundleMetadata;
 use crate::data::anybundle::outcomes::{LoadingError, LoadingResult};
+use crate::data::cache::version_url;
+use std::io::{Read, Seek};
 use std::path::Path;
 
 pub mod globals;
@@ -22,6 +25,9 @@ pub mod format;
 /// [Core Bundle]: https://developer.riotgames.com/docs/lor#data-dragon_core-bundles
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct CoreBundle {
+    /// The locale of the bundle, such as `en_us`.
+    pub locale: String,
+
     /// The contents of the `[locale]/data/globals-[locale].json` file.
     pub globals: globals::LocalizedGlobalsVecs,
 }
@@ -43,6 +49,40 @@ impl CoreBundle {
         let globals = globals::LocalizedGlobalsVecs::load(globals_path)?;
 
         Ok(CoreBundle {
+            locale: locale.clone(),
+            globals,
+        })
+    }
+
+    /// Load a Core Bundle from either a directory or a zip archive, depending on the extension of the given path.
+    pub fn load_any(path: &Path) -> LoadingResult<Self> {
+        match is_archive(path) {
+            true => Self::load_zip(path),
+            false => Self::load(path),
+        }
+    }
+
+    /// Load a Core Bundle zip archive, such as `core-en_us.zip`, to create a [CoreBundle] instance.
+    pub fn load_zip(archive_path: &Path) -> LoadingResult<Self> {
+        log::debug!("Loading CoreBundle from archive: {}", archive_path.as_os_str().to_string_lossy());
+
+        Self::from_archive(BundleArchive::open(archive_path)?)
+    }
+
+    /// Read a Core Bundle zip archive from the given reader, such as a [std::io::Cursor] over an in-memory buffer, to create a [CoreBundle] instance.
+    pub fn from_zip<R: Read + Seek>(reader: R) -> LoadingResult<Self> {
+        Self::from_archive(BundleArchive::new(reader)?)
+    }
+
+    /// Read the globals of a Core Bundle from a [BundleArchive].
+    fn from_archive<R: Read + Seek>(mut archive: BundleArchive<R>) -> LoadingResult<Self> {
+        let metadata = archive.metadata()?;
+        let locale = metadata.locale().ok_or(LoadingError::GettingLocale)?;
+
+        let globals = archive.read_json::<globals::LocalizedGlobalsVecs>(&format!("{}/data/globals-{}.json", locale, locale))?;
+
+        Ok(CoreBundle {
+            locale: locale.clone(),
             globals,
         })
     }
@@ -64,38 +104,71 @@ impl CoreBundle {
 
         log::debug!("Fetched CoreBundle: it defines {} regions, {} keywords, {} rarities, {} sets, {} spell speeds, and {} vocab terms!", &globals.regions.len(), &globals.keywords.len(), &globals.rarities.len(), &globals.sets.len(), &globals.spell_speeds.len(), &globals.vocab_terms.len());
 
-        Ok(Self {globals})
+        Ok(Self {locale: locale.to_string(), globals})
     }
 }
 
 
-/// Create [`globals::LocalizedGlobalsIndexes`] from the core bundle in the current working directory.
+/// Load the [CoreBundle] in the current working directory, returning an error if it cannot be loaded.
 ///
-/// This function tries to load data from the first directory matching the [glob] `./data/core-*`.
-pub fn create_globalindexes_from_wd() -> globals::LocalizedGlobalsIndexes {
+/// This function loads the first directory or zip archive matching the [glob] `./data/core-*`.
+pub fn try_load_corebundle_from_wd() -> LoadingResult<CoreBundle> {
     let path = glob::glob("./data/core-*")
         .expect("glob to be a valid glob")
-        .filter_map(Some)
         .find_map(Result::ok)
-        .expect("a valid core bundle to exist");
+        .ok_or(LoadingError::NoBundleFound)?;
 
-    let core = CoreBundle::load(&path)
-        .expect("to be able to load CoreBundle bundle");
+    CoreBundle::load_any(&path)
+}
+
+/// Create [`globals::LocalizedGlobalsIndexes`] from the core bundle in the current working directory, returning an error if it cannot be loaded.
+///
+/// See [`create_globalindexes_from_wd`].
+pub fn try_create_globalindexes_from_wd() -> LoadingResult<globals::LocalizedGlobalsIndexes> {
+    let core = try_load_corebundle_from_wd()?;
 
-    globals::LocalizedGlobalsIndexes::from(core.globals)
+    Ok(globals::LocalizedGlobalsIndexes::from(core.globals))
 }
 
+/// Create [`globals::LocalizedGlobalsIndexes`] from the core bundle in the current working directory.
+///
+/// This function tries to load data from the first directory or zip archive matching the [glob] `./data/core-*`.
+///
+/// # Panics
+///
+/// If no core bundle exists, or if it cannot be loaded.
+pub fn create_globalindexes_from_wd() -> globals::LocalizedGlobalsIndexes {
+    try_create_globalindexes_from_wd()
+        .expect("to be able to load CoreBundle bundle")
+}
+
+
+/// Create [`globals::LocalizedGlobalsIndexes`] from the given version of Data Dragon, such as `5_9_0` or `latest`, returning an error if it cannot be fetched.
+pub async fn try_create_globalindexes_from_dd(version: &str, locale: &str) -> LoadingResult<globals::LocalizedGlobalsIndexes> {
+    let client = reqwest::Client::new();
+
+    let core = CoreBundle::fetch(&client, &version_url(version)?, locale).await?;
+
+    Ok(globals::LocalizedGlobalsIndexes::from(core.globals))
+}
+
+/// Create [`globals::LocalizedGlobalsIndexes`] from the latest data in Data Dragon, returning an error if it cannot be fetched.
+///
+/// See [`create_globalindexes_from_dd_latest`].
+pub async fn try_create_globalindexes_from_dd_latest(locale: &str) -> LoadingResult<globals::LocalizedGlobalsIndexes> {
+    try_create_globalindexes_from_dd("latest", locale).await
+}
 
 /// Create [`globals::LocalizedGlobalsIndexes`] from the latest data in Data Dragon.
 ///
 /// This function tries to load data from `https://dd.b.pvp.net/latest`.
+///
+/// # Panics
+///
+/// If the core bundle cannot be fetched.
 pub async fn create_globalindexes_from_dd_latest(locale: &str) -> globals::LocalizedGlobalsIndexes {
-    let client = reqwest::Client::new();
-
-    let core = CoreBundle::fetch(&client, "https://dd.b.pvp.net/latest", locale).await
-        .expect("to be able to fetch CoreBundle");
-
-    globals::LocalizedGlobalsIndexes::from(core.globals)
+    try_create_globalindexes_from_dd_latest(locale).await
+        .expect("to be able to fetch CoreBundle")
 }
 
 
@@ -1,27 +1,84 @@
 //! Module defining structs representing localized card sets.
 
 use crate::data::setbundle::set::CardSet;
+use serde::de::IntoDeserializer;
+use serde::Deserialize;
 use std::collections::HashMap;
 
 /// A Legends of Runeterra [CardSet], and its associated localization.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(from = "RawLocalizedCardSet", into = "RawLocalizedCardSet")]
 pub struct LocalizedCardSet {
     /// The [CardSet] these strings refer to.
-    #[serde(rename = "nameRef")]
     pub set: CardSet,
 
+    /// The internal name of the set, such as `Set6cde`, which is preserved even if the [CardSet] is [CardSet::Unsupported].
+    pub name_ref: String,
+
     /// The localized name of the set.
     pub name: String,
 
     /// URL to the icon of the set in `.png` format.
-    #[serde(rename = "iconAbsolutePath")]
     pub icon_png: String,
 }
 
+impl LocalizedCardSet {
+    /// Get the numeric id of the set from the digits of its [LocalizedCardSet::name_ref], such as `Set7b` → `7`.
+    ///
+    /// Returns [Option::None] for sets without digits, such as [CardSet::Events].
+    pub fn id(&self) -> Option<u32> {
+        self.name_ref
+            .chars()
+            .filter(char::is_ascii_digit)
+            .collect::<String>()
+            .parse()
+            .ok()
+    }
+}
+
+/// The serialized form of a [LocalizedCardSet], with the `nameRef` kept as a string.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+struct RawLocalizedCardSet {
+    #[serde(rename = "nameRef")]
+    name_ref: String,
+    name: String,
+    #[serde(rename = "iconAbsolutePath")]
+    icon_png: String,
+}
+
+impl From<RawLocalizedCardSet> for LocalizedCardSet {
+    fn from(raw: RawLocalizedCardSet) -> Self {
+        let deserializer: serde::de::value::StrDeserializer<serde::de::value::Error> =
+            raw.name_ref.as_str().into_deserializer();
+        let set = CardSet::deserialize(deserializer).unwrap_or(CardSet::Unsupported);
+
+        Self {
+            set,
+            name_ref: raw.name_ref,
+            name: raw.name,
+            icon_png: raw.icon_png,
+        }
+    }
+}
+
+impl From<LocalizedCardSet> for RawLocalizedCardSet {
+    fn from(set: LocalizedCardSet) -> Self {
+        Self {
+            name_ref: set.name_ref,
+            name: set.name,
+            icon_png: set.icon_png,
+        }
+    }
+}
+
 /// How [LocalizedCardSet]s appear in `global.json` files.
 pub type LocalizedCardSetVec = Vec<LocalizedCardSet>;
 /// An index of [LocalizedCardSet]s, with [LocalizedCardSet::set]s as keys.
 pub type LocalizedCardSetIndex = HashMap<CardSet, LocalizedCardSet>;
+/// An index of the [LocalizedCardSet]s unknown to this version of the crate, with their [LocalizedCardSet::id]s as keys.
+///
+/// They are all [CardSet::Unsupported], so they cannot be told apart in a [LocalizedCardSetIndex].
+pub type LocalizedUnsupportedCardSetIndex = HashMap<u32, LocalizedCardSet>;
 
 #[cfg(test)]
 mod tests {
@@ -40,9 +97,22 @@ mod tests {
             "#).unwrap(),
             LocalizedCardSet {
                 set: CardSet::CallOfTheMountain,
+                name_ref: "Set3".to_string(),
                 name: "Call of the Mountain".to_string(),
                 icon_png: "http://dd.b.pvp.net/3_11_0/core/en_us/img/sets/set3_crispmip.png".to_string(),
             }
         );
     }
+
+    #[test]
+    fn unsupported_sets_keep_their_id() {
+        let set = serde_json::de::from_str::<'static, LocalizedCardSet>(r#"{"iconAbsolutePath": "", "name": "Future", "nameRef": "Set12"}"#).unwrap();
+        assert_eq!(set.set, CardSet::Unsupported);
+        assert_eq!(set.id(), Some(12));
+
+        let darkin = serde_json::de::from_str::<'static, LocalizedCardSet>(r#"{"iconAbsolutePath": "", "name": "The Darkin Saga", "nameRef": "Set6cde"}"#).unwrap();
+        assert_eq!(darkin.id(), Some(6));
+        assert_eq!(darkin.set, CardSet::TheDarkinSaga);
+        assert_eq!(serde_json::to_string(&darkin).unwrap(), r#"{"nameRef":"Set6cde","name":"The Darkin Saga","iconAbsolutePath":""}"#);
+    }
 }
@@ -0,0 +1,571 @@
+//! Module defining [`CardDatabase`], which holds together all the data loaded from [Data Dragon] for a single locale, and [`MultiLocaleDatabase`], which holds it for multiple locales.
+//!
+//! [Data Dragon]: https://developer.riotgames.com/docs/lor#data-dragon
+
+use crate::data::anybundle::outcomes::{BundleFailure, BundlesResult, LoadingError, LoadingResult, PartialLoad};
+use crate::data::cache::{create_indexes_from_cache, BundleCache};
+use crate::data::corebundle::globals::LocalizedGlobalsIndexes;
+use crate::data::corebundle::{try_create_globalindexes_from_dd, try_load_corebundle_from_wd};
+use crate::data::deckcode::origins::ORIGINS_LOCALE;
+use crate::data::deckcode::registry::registry_mut;
+use crate::data::setbundle::card::{Card, CardIndex};
+use crate::data::setbundle::code::CardCode;
+use crate::data::setbundle::region::CardRegion;
+use crate::data::setbundle::set::CardSet;
+use crate::data::setbundle::supertype::CardSupertype;
+use crate::data::setbundle::{create_cardindex_from_dd_partial, create_cardindex_from_wd_partial};
+use crate::data::setbundle::art::CardArt;
+use crate::data::setbundle::subtype::CardSubtype;
+use itertools::Itertools;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// The cards and the game globals of a single locale, together with the bundle version they were loaded from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CardDatabase {
+    /// The cards of the database.
+    pub cards: CardIndex,
+
+    /// The localization of game globals.
+    pub globals: LocalizedGlobalsIndexes,
+
+    /// The locale of the data, such as `en_us`.
+    pub locale: String,
+
+    /// The Data Dragon version the data was loaded from, such as `5_9_0` or `latest`, or [`None`] if it is not known.
+    pub version: Option<String>,
+}
+
+impl CardDatabase {
+    /// Create a new [`CardDatabase`] from already loaded data.
+    pub fn new(cards: CardIndex, globals: LocalizedGlobalsIndexes, locale: String, version: Option<String>) -> Self {
+        Self { cards, globals, locale, version }
+    }
+
+    /// Load a [`CardDatabase`] from the bundles in the current working directory, skipping the sets which cannot be loaded.
+    ///
+    /// See [`create_cardindex_from_wd_partial`] and [`try_load_corebundle_from_wd`].
+    pub fn from_wd_partial() -> LoadingResult<PartialLoad<Self>> {
+        let core = try_load_corebundle_from_wd()?;
+        let cards = create_cardindex_from_wd_partial();
+
+        Ok(PartialLoad {
+            value: Self::new(cards.value, LocalizedGlobalsIndexes::from(core.globals), core.locale, None),
+            failures: cards.failures,
+        })
+    }
+
+    /// Load a [`CardDatabase`] from the bundles in the current working directory, failing with every [`BundleFailure`] if any of them cannot be loaded.
+    pub fn try_from_wd() -> BundlesResult<Self> {
+        Self::from_wd_partial()
+            .map_err(|error| vec![BundleFailure { bundle: "./data/core-*".to_string(), error }])?
+            .into_result()
+    }
+
+    /// Load a [`CardDatabase`] from the bundles in the current working directory.
+    ///
+    /// # Panics
+    ///
+    /// If any of the bundles cannot be loaded.
+    pub fn from_wd() -> Self {
+        Self::try_from_wd()
+            .expect("to be able to load the bundles in the working directory")
+    }
+
+    /// Load a [`CardDatabase`] from the given version of Data Dragon, such as `5_9_0` or `latest`, skipping the sets which cannot be fetched.
+    ///
+    /// Fails with [`LoadingError::InvalidVersion`] if the version is not valid.
+    pub async fn from_dd_partial(version: &str, locale: &str, known_set_codes: &[&str]) -> LoadingResult<PartialLoad<Self>> {
+        let globals = try_create_globalindexes_from_dd(version, locale).await?;
+        let cards = create_cardindex_from_dd_partial(version, locale, known_set_codes.iter().copied()).await;
+
+        Ok(PartialLoad {
+            value: Self::new(cards.value, globals, locale.to_string(), Some(version.to_string())),
+            failures: cards.failures,
+        })
+    }
+
+    /// Load a [`CardDatabase`] from the latest data in Data Dragon, skipping the sets which cannot be fetched.
+    pub async fn from_dd_latest_partial(locale: &str, known_set_codes: &[&str]) -> LoadingResult<PartialLoad<Self>> {
+        Self::from_dd_partial("latest", locale, known_set_codes).await
+    }
+
+    /// Load a [`CardDatabase`] from the given [`BundleCache`], downloading the bundles which are not cached yet, and skipping the sets which cannot be loaded.
+    ///
+    /// See [`create_indexes_from_cache`].
+    pub async fn from_cache_partial(cache: &BundleCache, version: &str, locale: &str, known_set_codes: &[&str]) -> LoadingResult<PartialLoad<Self>> {
+        let (globals, cards) = create_indexes_from_cache(cache, version, locale, known_set_codes).await?;
+
+        Ok(PartialLoad {
+            value: Self::new(cards.value, globals, locale.to_string(), Some(version.to_string())),
+            failures: cards.failures,
+        })
+    }
+
+    /// Add the sets and regions of this database to the process-wide [`CardIdRegistry`](crate::data::deckcode::registry::CardIdRegistry), so that its cards can be used in deck codes.
+    pub fn register(&self) {
+        let mut registry = registry_mut();
+        registry.extend_from_globals(&self.globals);
+        registry.extend_from_cards(self.cards.values());
+    }
+
+    /// Get the [`Card`] with the given code.
+    pub fn card(&self, code: &CardCode) -> Option<&Card> {
+        self.cards.get(code)
+    }
+
+    /// Get all the [`Card`]s with the given name, ignoring case.
+    ///
+    /// Multiple cards may share the same name, such as the levels of a champion.
+    pub fn by_name(&self, name: &str) -> Vec<&Card> {
+        let name = name.to_lowercase();
+
+        self.cards
+            .values()
+            .filter(|card| card.name.to_lowercase() == name)
+            .sorted_by(|a, b| a.code.cmp(&b.code))
+            .collect()
+    }
+
+    /// Get an [`Iterator`] of all the [`Card`]s of the given [`CardSet`].
+    pub fn in_set(&self, set: CardSet) -> impl Iterator<Item = &Card> {
+        self.cards.values().filter(move |card| card.set == set)
+    }
+
+    /// Get an [`Iterator`] of all the [`Card`]s belonging to the given [`CardRegion`], including the ones belonging to multiple regions.
+    pub fn in_region(&self, region: CardRegion) -> impl Iterator<Item = &Card> {
+        self.cards.values().filter(move |card| card.regions.contains(&region))
+    }
+
+    /// Get an [`Iterator`] of all the champion [`Card`]s, including their higher levels.
+    pub fn champions(&self) -> impl Iterator<Item = &Card> {
+        self.cards.values().filter(|card| card.supertype == CardSupertype::Champion)
+    }
+
+    /// Get an [`Iterator`] of all the [`Card`]s which can be added to decks.
+    pub fn collectible(&self) -> impl Iterator<Item = &Card> {
+        self.cards.values().filter(|card| card.collectible)
+    }
+}
+
+/// The fields of a [`Card`] which change between locales.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalizedCardText {
+    /// Localized name of the card.
+    pub name: String,
+    /// Art assets of the card, whose URLs depend on the locale.
+    pub art: Vec<CardArt>,
+    /// Localized description of the card, in pseudo-XML.
+    pub localized_description_xml: String,
+    /// Localized description of the card, in plain text.
+    pub localized_description_text: String,
+    /// Localized level up text of the card, in pseudo-XML.
+    pub localized_levelup_xml: String,
+    /// Localized level up text of the card, in plain text.
+    pub localized_levelup_text: String,
+    /// Localized flavor text of the card.
+    pub localized_flavor_text: String,
+    /// Localized subtypes of the card.
+    pub subtypes: Vec<CardSubtype>,
+    /// Localized names of the regions of the card.
+    pub(crate) localized_regions: Vec<String>,
+    /// Localized name of the spell speed of the card.
+    pub(crate) localized_spell_speed: String,
+    /// Localized names of the keywords of the card.
+    pub(crate) localized_keywords: Vec<String>,
+    /// Localized names of the cards associated with the card.
+    pub(crate) associated_card_names_localized: Vec<String>,
+}
+
+impl LocalizedCardText {
+    /// Extract the localized fields of the given [`Card`].
+    pub fn of(card: &Card) -> Self {
+        Self {
+            name: card.name.clone(),
+            art: card.art.clone(),
+            localized_description_xml: card.localized_description_xml.clone(),
+            localized_description_text: card.localized_description_text.clone(),
+            localized_levelup_xml: card.localized_levelup_xml.clone(),
+            localized_levelup_text: card.localized_levelup_text.clone(),
+            localized_flavor_text: card.localized_flavor_text.clone(),
+            subtypes: card.subtypes.clone(),
+            localized_regions: card.localized_regions.clone(),
+            localized_spell_speed: card.localized_spell_speed.clone(),
+            localized_keywords: card.localized_keywords.clone(),
+            associated_card_names_localized: card.associated_card_names_localized.clone(),
+        }
+    }
+
+    /// Replace the localized fields of the given [`Card`] with these ones.
+    pub fn apply_to(&self, card: &mut Card) {
+        card.name = self.name.clone();
+        card.art = self.art.clone();
+        card.localized_description_xml = self.localized_description_xml.clone();
+        card.localized_description_text = self.localized_description_text.clone();
+        card.localized_levelup_xml = self.localized_levelup_xml.clone();
+        card.localized_levelup_text = self.localized_levelup_text.clone();
+        card.localized_flavor_text = self.localized_flavor_text.clone();
+        card.subtypes = self.subtypes.clone();
+        card.localized_regions = self.localized_regions.clone();
+        card.localized_spell_speed = self.localized_spell_speed.clone();
+        card.localized_keywords = self.localized_keywords.clone();
+        card.associated_card_names_localized = self.associated_card_names_localized.clone();
+    }
+}
+
+/// Normalize a locale to the format used by Data Dragon, such as `pt_br` for `pt-BR`.
+pub fn normalize_locale(locale: &str) -> String {
+    locale.trim().to_lowercase().replace('-', "_")
+}
+
+/// Parse a comma-separated list of locales, such as the one in the `DATA_DRAGON_LOCALE` environment variable, skipping empty entries.
+pub fn parse_locales(list: &str) -> Vec<&str> {
+    list
+        .split(',')
+        .map(str::trim)
+        .filter(|locale| !locale.is_empty())
+        .collect()
+}
+
+/// Find the locale among the available ones which best matches the requested one, first exactly, then by language.
+///
+/// If multiple locales have the requested language, the first one is returned.
+pub fn match_locale<'l>(requested: &str, available: impl IntoIterator<Item = &'l str>) -> Option<&'l str> {
+    let requested = normalize_locale(requested);
+    let language = |locale: &str| normalize_locale(locale).split('_').next().unwrap_or_default().to_string();
+
+    let available = available.into_iter().collect_vec();
+    available
+        .iter()
+        .find(|locale| normalize_locale(locale) == requested)
+        .or_else(|| available.iter().find(|locale| language(locale) == language(&requested)))
+        .copied()
+}
+
+/// The cards and the game globals of multiple locales.
+///
+/// The locale-independent data of the cards, such as their cost or their regions, is taken from the database of the default locale and shared by all the others, which only store the [`LocalizedCardText`] of each card.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiLocaleDatabase {
+    /// The database of the default locale.
+    pub default: CardDatabase,
+
+    /// The localized texts of the cards, for every locale except the default one.
+    texts: HashMap<String, HashMap<CardCode, LocalizedCardText>>,
+
+    /// The localized globals, for every locale except the default one.
+    globals: HashMap<String, LocalizedGlobalsIndexes>,
+
+    /// The texts of the cards in the [`ORIGINS_LOCALE`], if it was added only to check the Origins of decks.
+    origin_texts: Option<HashMap<CardCode, LocalizedCardText>>,
+}
+
+impl MultiLocaleDatabase {
+    /// Create a new [`MultiLocaleDatabase`] using the given [`CardDatabase`] for the default locale.
+    pub fn new(default: CardDatabase) -> Self {
+        Self { default, texts: HashMap::new(), globals: HashMap::new(), origin_texts: None }
+    }
+
+    /// Load a [`MultiLocaleDatabase`] with the given locales, [normalized](normalize_locale), the first of which is the default one, skipping the sets which cannot be loaded.
+    ///
+    /// If a [`BundleCache`] is given, the bundles of the given version are loaded from it, and downloaded if they are not cached yet; otherwise, they are fetched directly from Data Dragon.
+    ///
+    /// If the [`ORIGINS_LOCALE`] is not among the given ones, its cards are loaded too and [added only to check the Origins of decks](Self::add_origin_locale), so that startup requires to fetch the bundles of an additional locale.
+    ///
+    /// Sets which cannot be loaded are reported as [`BundleFailure`]s whose bundle is prefixed by the locale, such as `it_it/set1`; fails if no locale is given, or if the Core Bundle of any locale cannot be loaded.
+    pub async fn load(locales: &[&str], version: &str, cache: Option<&BundleCache>, known_set_codes: &[&str]) -> LoadingResult<PartialLoad<Self>> {
+        let locales = locales.iter().map(|locale| normalize_locale(locale)).unique().collect_vec();
+        let mut failures = vec![];
+
+        let (default, others) = locales.split_first().ok_or(LoadingError::NoBundleFound)?;
+        let mut database = Self::new(Self::load_locale(default, version, cache, known_set_codes, &mut failures).await?);
+        for locale in others {
+            database.add_locale(Self::load_locale(locale, version, cache, known_set_codes, &mut failures).await?);
+        }
+
+        if !locales.iter().any(|locale| locale == ORIGINS_LOCALE) {
+            log::debug!("Also loading the {} locale, to check the Origins of decks ...", ORIGINS_LOCALE);
+            database.add_origin_locale(Self::load_locale(ORIGINS_LOCALE, version, cache, known_set_codes, &mut failures).await?);
+        }
+
+        Ok(PartialLoad { value: database, failures })
+    }
+
+    /// Load the [`CardDatabase`] of a single locale for [`MultiLocaleDatabase::load`], adding the sets which cannot be loaded to the given failures.
+    async fn load_locale(locale: &str, version: &str, cache: Option<&BundleCache>, known_set_codes: &[&str], failures: &mut Vec<BundleFailure>) -> LoadingResult<CardDatabase> {
+        let localized = match cache {
+            Some(cache) => {
+                log::debug!("Loading {} {} CardDatabase from the cache in {} ...", version, locale, cache.root.to_string_lossy());
+                CardDatabase::from_cache_partial(cache, version, locale, known_set_codes).await
+            }
+            None => {
+                log::debug!("Loading {} {} CardDatabase from Data Dragon ...", version, locale);
+                CardDatabase::from_dd_partial(version, locale, known_set_codes).await
+            }
+        };
+        let localized = localized.inspect_err(|error| {
+            log::error!("Could not load the {} CardDatabase, as its CoreBundle could not be loaded: {}", locale, error);
+        })?;
+
+        failures.extend(localized.failures.into_iter().map(|failure| BundleFailure {
+            bundle: format!("{}/{}", locale, failure.bundle),
+            error: failure.error,
+        }));
+
+        Ok(localized.value)
+    }
+
+    /// Add the texts and globals of the locale of the given [`CardDatabase`], replacing the ones previously added for it.
+    ///
+    /// Cards missing from the database of the default locale are ignored; cards missing from the given database keep the texts of the default locale.
+    pub fn add_locale(&mut self, database: CardDatabase) {
+        let locale = normalize_locale(&database.locale);
+        if locale == normalize_locale(&self.default.locale) {
+            self.default = database;
+            return;
+        }
+
+        let texts = database.cards
+            .values()
+            .filter(|card| self.default.cards.contains_key(&card.code))
+            .map(|card| (card.code.clone(), LocalizedCardText::of(card)))
+            .collect();
+
+        self.texts.insert(locale.clone(), texts);
+        self.globals.insert(locale, database.globals);
+    }
+
+    /// The default locale.
+    pub fn default_locale(&self) -> &str {
+        &self.default.locale
+    }
+
+    /// All the loaded locales, starting from the default one.
+    pub fn locales(&self) -> Vec<&str> {
+        std::iter::once(self.default.locale.as_str())
+            .chain(self.texts.keys().map(String::as_str).sorted())
+            .collect()
+    }
+
+    /// Find the loaded locale which best matches the requested one, such as a Telegram language code or a Discord locale.
+    ///
+    /// Locales match exactly first, then by language; if no loaded locale matches, or if no locale is requested, the default one is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use patched_porobot::data::database::{CardDatabase, MultiLocaleDatabase};
+    ///
+    /// let database = MultiLocaleDatabase::new(CardDatabase::from_wd());
+    ///
+    /// assert_eq!(database.resolve_locale(Some("en-GB")), "en_us");
+    /// assert_eq!(database.resolve_locale(Some("it")), "en_us");
+    /// assert_eq!(database.resolve_locale(None), "en_us");
+    /// ```
+    pub fn resolve_locale(&self, requested: Option<&str>) -> &str {
+        requested
+            .and_then(|requested| match_locale(requested, self.locales()))
+            .unwrap_or_else(|| self.default_locale())
+    }
+
+    /// Get the [`Card`] with the given code, localized in the given loaded locale.
+    pub fn card(&self, code: &CardCode, locale: &str) -> Option<Cow<'_, Card>> {
+        let card = self.default.card(code)?;
+        let locale = normalize_locale(locale);
+
+        if locale == normalize_locale(&self.default.locale) {
+            return Some(Cow::Borrowed(card));
+        }
+
+        let text = self.texts.get(&locale)?.get(code);
+        match text {
+            Some(text) => {
+                let mut card = card.clone();
+                text.apply_to(&mut card);
+                Some(Cow::Owned(card))
+            }
+            None => Some(Cow::Borrowed(card)),
+        }
+    }
+
+    /// Get the [`LocalizedGlobalsIndexes`] of the given loaded locale.
+    pub fn globals(&self, locale: &str) -> Option<&LocalizedGlobalsIndexes> {
+        let locale = normalize_locale(locale);
+
+        match locale == normalize_locale(&self.default.locale) {
+            true => Some(&self.default.globals),
+            false => self.globals.get(&locale),
+        }
+    }
+
+    /// Build a [`CardIndex`] containing only the cards with the given codes, localized in the given loaded locale.
+    ///
+    /// Useful to display a few cards, such as the ones of a [`Deck`](crate::data::deckcode::deck::Deck), without copying the whole database; unknown codes are skipped.
+    pub fn localized_cards<'c>(&self, codes: impl IntoIterator<Item = &'c CardCode>, locale: &str) -> CardIndex {
+        codes
+            .into_iter()
+            .filter_map(|code| self.card(code, locale))
+            .map(|card| (card.code.clone(), card.into_owned()))
+            .collect()
+    }
+
+    /// Add the texts of the [`ORIGINS_LOCALE`] from the given [`CardDatabase`], only to [check the Origins of decks](Self::origin_cards) with.
+    ///
+    /// Unlike [`MultiLocaleDatabase::add_locale`], the locale is not added to the [loaded locales](Self::locales), and its globals are discarded.
+    pub fn add_origin_locale(&mut self, database: CardDatabase) {
+        let texts = database.cards
+            .values()
+            .filter(|card| self.default.cards.contains_key(&card.code))
+            .map(|card| (card.code.clone(), LocalizedCardText::of(card)))
+            .collect();
+
+        self.origin_texts = Some(texts);
+    }
+
+    /// Build a [`CardIndex`] containing only the cards with the given codes, localized in the [`ORIGINS_LOCALE`], to check the legality of a [`Deck`](crate::data::deckcode::deck::Deck) with.
+    ///
+    /// Uses the texts [added for the Origins](Self::add_origin_locale) if the [`ORIGINS_LOCALE`] is not loaded, and falls back to the default locale if neither are available.
+    pub fn origin_cards<'c>(&self, codes: impl IntoIterator<Item = &'c CardCode>) -> CardIndex {
+        if self.globals(ORIGINS_LOCALE).is_some() {
+            return self.localized_cards(codes, ORIGINS_LOCALE);
+        }
+
+        match &self.origin_texts {
+            Some(texts) => codes
+                .into_iter()
+                .filter_map(|code| self.default.card(code))
+                .map(|card| {
+                    let mut card = card.clone();
+                    if let Some(text) = texts.get(&card.code) {
+                        text.apply_to(&mut card);
+                    }
+                    (card.code.clone(), card)
+                })
+                .collect(),
+            None => self.localized_cards(codes, self.default_locale()),
+        }
+    }
+
+    /// Build the [`CardDatabase`] of the given loaded locale, copying all of its cards.
+    pub fn database(&self, locale: &str) -> Option<CardDatabase> {
+        let globals = self.globals(locale)?.clone();
+        let cards = self.localized_cards(self.default.cards.keys(), locale);
+
+        Some(CardDatabase::new(cards, globals, normalize_locale(locale), self.default.version.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::cache::tests::{copy_bundle, temporary_cache};
+    use std::fs;
+
+    #[test]
+    fn lookups() {
+        let database = CardDatabase::from_wd();
+        assert_eq!(database.locale, "en_us");
+        assert_eq!(database.version, None);
+
+        let braum = database.by_name("BRAUM");
+        assert!(!braum.is_empty());
+        assert_eq!(braum[0].code.full, "01FR009");
+        assert_eq!(database.card(&CardCode::from("01FR009".to_string())), Some(braum[0]));
+
+        assert!(database.in_set(CardSet::Foundations).all(|card| card.code.set() == "01"));
+        assert!(database.in_region(CardRegion::Freljord).any(|card| card.name == "Braum"));
+        assert!(database.champions().any(|card| card.name == "Braum" && !card.collectible));
+        assert!(database.collectible().all(|card| card.collectible));
+        assert!(database.by_name("Not a card").is_empty());
+    }
+
+    #[test]
+    fn multiple_locales() {
+        let english = CardDatabase::from_wd();
+
+        let mut italian = english.clone();
+        italian.locale = "it_it".to_string();
+        let code = CardCode::from("01FR009".to_string());
+        italian.cards.get_mut(&code).unwrap().name = "Braum, ma in italiano".to_string();
+        italian.cards.get_mut(&code).unwrap().cost = 99;
+
+        let mut database = MultiLocaleDatabase::new(english);
+        database.add_locale(italian);
+
+        assert_eq!(database.locales(), vec!["en_us", "it_it"]);
+        assert_eq!(database.resolve_locale(Some("it")), "it_it");
+        assert_eq!(database.resolve_locale(Some("IT-it")), "it_it");
+        assert_eq!(database.resolve_locale(Some("fr")), "en_us");
+
+        let braum = database.card(&code, "it_it").unwrap();
+        assert_eq!(braum.name, "Braum, ma in italiano");
+        assert_ne!(braum.cost, 99);
+        assert_eq!(database.card(&code, "en_us").unwrap().name, "Braum");
+        assert!(database.card(&code, "fr_fr").is_none());
+
+        let view = database.database("it_it").unwrap();
+        assert_eq!(view.locale, "it_it");
+        assert_eq!(view.card(&code).unwrap().name, "Braum, ma in italiano");
+        assert!(database.database("fr_fr").is_none());
+
+        let subset = database.localized_cards([&code, &CardCode::from("99XX999".to_string())], "it_it");
+        assert_eq!(subset.len(), 1);
+        assert_eq!(subset[&code].name, "Braum, ma in italiano");
+    }
+
+    #[test]
+    fn origin_cards() {
+        let english = CardDatabase::from_wd();
+        let code = CardCode::from("01FR009".to_string());
+
+        let mut italian = english.clone();
+        italian.locale = "it_it".to_string();
+        italian.cards.get_mut(&code).unwrap().name = "Braum, ma in italiano".to_string();
+
+        let mut database = MultiLocaleDatabase::new(italian);
+        assert_eq!(database.origin_cards([&code])[&code].name, "Braum, ma in italiano");
+
+        database.add_origin_locale(english.clone());
+        assert_eq!(database.origin_cards([&code])[&code].name, "Braum");
+        assert_eq!(database.card(&code, "it_it").unwrap().name, "Braum, ma in italiano");
+        assert_eq!(database.locales(), vec!["it_it"]);
+        assert_eq!(database.resolve_locale(Some("en")), "it_it");
+        assert!(database.globals(ORIGINS_LOCALE).is_none());
+
+        database.add_locale(english);
+        assert_eq!(database.origin_cards([&code])[&code].name, "Braum");
+    }
+
+    #[test]
+    fn locale_lists() {
+        assert_eq!(parse_locales("it_it, en_us,,"), vec!["it_it", "en_us"]);
+        assert!(parse_locales(" ").is_empty());
+    }
+
+    #[tokio::test]
+    async fn load_from_cache() {
+        let cache = temporary_cache("database");
+        copy_bundle(&cache, "5_9_0", "core-en_us");
+        copy_bundle(&cache, "5_9_0", "set1-en_us");
+
+        let result = MultiLocaleDatabase::load(&[], "5_9_0", Some(&cache), &["set1"]).await;
+        assert!(matches!(result, Err(LoadingError::NoBundleFound)));
+
+        let database = MultiLocaleDatabase::load(&["en-US"], "5_9_0", Some(&cache), &["set1"]).await.unwrap();
+        assert!(database.is_complete());
+        assert_eq!(database.value.locales(), vec!["en_us"]);
+        assert_eq!(database.value.default.version, Some("5_9_0".to_string()));
+        assert!(database.value.default.cards.keys().all(|code| code.full.starts_with("01")));
+
+        fs::remove_dir_all(&cache.root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn load_invalid_version() {
+        let result = MultiLocaleDatabase::load(&["en_us"], "../5_9_0", None, &["set1"]).await;
+        assert!(matches!(result, Err(LoadingError::InvalidVersion(_))));
+
+        let cards = create_cardindex_from_dd_partial("../5_9_0", "en_us", ["set1"].into_iter()).await;
+        assert!(matches!(cards.failures[0].error, LoadingError::InvalidVersion(_)));
+    }
+}
@@ -0,0 +1,452 @@
+//! Module defining similarity metrics between [`Deck`]s, the clustering of many decks into [`Archetype`]s, and the generation of conventional archetype names such as `Ezreal Draven (PZ/NX)`.
+
+use crate::data::corebundle::region::LocalizedCardRegionIndex;
+use crate::data::deckcode::deck::Deck;
+use crate::data::setbundle::card::CardIndex;
+use crate::data::setbundle::code::CardCode;
+use crate::data::setbundle::region::CardRegion;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// The maximum number of regions considered when comparing the regions of two [`Deck`]s.
+const REGIONS_LIMIT: usize = 3;
+
+/// The maximum number of champions included in an archetype name.
+const NAMED_CHAMPIONS: usize = 2;
+
+/// Compute the weighted Jaccard similarity of the cards of two [`Deck`]s: the sum of the minimum number of copies of each card, divided by the sum of the maximum.
+///
+/// Returns `1.0` if both decks are empty.
+///
+/// # Example
+///
+/// ```rust
+/// use patched_porobot::deck;
+/// use patched_porobot::data::deckcode::archetype::weighted_jaccard;
+/// use patched_porobot::data::deckcode::deck::Deck;
+///
+/// let a: Deck = deck!["01DE002": 3, "01DE012": 1];
+/// let b: Deck = deck!["01DE002": 1, "01DE012": 1];
+/// assert_eq!(weighted_jaccard(&a, &b), 0.5);
+/// ```
+pub fn weighted_jaccard(a: &Deck, b: &Deck) -> f64 {
+    let codes: HashSet<&CardCode> = a.contents.keys().chain(b.contents.keys()).collect();
+
+    let (min, max) = codes
+        .into_iter()
+        .map(|code| (a.copies_of(code), b.copies_of(code)))
+        .fold((0, 0), |(min, max), (x, y)| (min + x.min(y), max + x.max(y)));
+
+    match max {
+        0 => 1.0,
+        _ => f64::from(min) / f64::from(max),
+    }
+}
+
+/// Compute the Jaccard similarity of two sets, returning `1.0` if both are empty.
+fn jaccard<T: Eq + Hash>(a: &HashSet<T>, b: &HashSet<T>) -> f64 {
+    let union = a.union(b).count();
+
+    match union {
+        0 => 1.0,
+        _ => a.intersection(b).count() as f64 / union as f64,
+    }
+}
+
+/// The features of a [`Deck`] compared by the similarity metrics.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeckProfile<'d> {
+    /// The profiled deck.
+    pub deck: &'d Deck,
+    /// The codes of the champions of the deck.
+    pub champions: HashSet<CardCode>,
+    /// The regions of the deck, or an empty set if its cards do not fit in 3 regions.
+    pub regions: HashSet<CardRegion>,
+}
+
+impl<'d> DeckProfile<'d> {
+    /// Profile the given [`Deck`] using the given [`CardIndex`].
+    pub fn of(deck: &'d Deck, cards: &CardIndex) -> Self {
+        Self {
+            deck,
+            champions: deck.champions(cards).map(|card| card.code.clone()).collect(),
+            regions: deck.regions(cards, REGIONS_LIMIT).unwrap_or_default(),
+        }
+    }
+}
+
+/// The similarity between two [`Deck`]s, according to multiple metrics, each ranging from `0.0` to `1.0`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DeckSimilarity {
+    /// The [weighted Jaccard similarity](weighted_jaccard) of the cards of the decks.
+    pub cards: f64,
+    /// The Jaccard similarity of the champions of the decks.
+    pub champions: f64,
+    /// The Jaccard similarity of the regions of the decks.
+    pub regions: f64,
+}
+
+impl DeckSimilarity {
+    /// Compare two [`DeckProfile`]s.
+    pub fn between(a: &DeckProfile, b: &DeckProfile) -> Self {
+        Self {
+            cards: weighted_jaccard(a.deck, b.deck),
+            champions: jaccard(&a.champions, &b.champions),
+            regions: jaccard(&a.regions, &b.regions),
+        }
+    }
+
+    /// Combine the metrics into a single similarity, using the given weights.
+    ///
+    /// Returns `0.0` if all weights are zero.
+    pub fn combined(&self, weights: &SimilarityWeights) -> f64 {
+        let total = weights.cards + weights.champions + weights.regions;
+        if total <= 0.0 {
+            return 0.0;
+        }
+
+        (self.cards * weights.cards + self.champions * weights.champions + self.regions * weights.regions) / total
+    }
+}
+
+/// The relative importance of each metric of [`DeckSimilarity`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SimilarityWeights {
+    /// The weight of [`DeckSimilarity::cards`].
+    pub cards: f64,
+    /// The weight of [`DeckSimilarity::champions`].
+    pub champions: f64,
+    /// The weight of [`DeckSimilarity::regions`].
+    pub regions: f64,
+}
+
+impl Default for SimilarityWeights {
+    fn default() -> Self {
+        Self {
+            cards: 0.5,
+            champions: 0.3,
+            regions: 0.2,
+        }
+    }
+}
+
+/// A group of similar [`Deck`]s, labeled by their dominant champions and regions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Archetype {
+    /// The indexes of the decks belonging to the archetype, in ascending order.
+    pub members: Vec<usize>,
+    /// The champions present in at least half of the decks, from the most to the least common.
+    pub champions: Vec<CardCode>,
+    /// The regions of at least half of the decks, from the most to the least common.
+    pub regions: Vec<CardRegion>,
+}
+
+impl Archetype {
+    /// Generate the conventional name of the archetype from its dominant champions and regions, like [`archetype_name`] does for a single [`Deck`].
+    pub fn name(&self, cards: &CardIndex, regions: &LocalizedCardRegionIndex) -> Option<String> {
+        let champions = self.champions
+            .iter()
+            .filter_map(|code| code.to_card(cards))
+            .map(|card| card.name.clone())
+            .unique()
+            .take(NAMED_CHAMPIONS)
+            .collect_vec();
+
+        format_name(champions, abbreviations(self.regions.iter(), regions))
+    }
+}
+
+/// Group the given [`Deck`]s into [`Archetype`]s with average-linkage agglomerative clustering.
+///
+/// Clusters are merged while the average [combined similarity](DeckSimilarity::combined) between their decks is at least `threshold`.
+///
+/// The similarities between decks are computed once, and the linkage of a merged cluster is derived from the linkages of its parts with the Lance–Williams formula.
+///
+/// Archetypes are sorted from the largest to the smallest, then by their first member.
+pub fn cluster_decks(decks: &[Deck], cards: &CardIndex, weights: &SimilarityWeights, threshold: f64) -> Vec<Archetype> {
+    let profiles = decks.iter().map(|deck| DeckProfile::of(deck, cards)).collect_vec();
+    let count = profiles.len();
+
+    // The average similarity between the decks of every pair of clusters, initially containing a single deck each
+    let mut linkage = vec![vec![0.0; count]; count];
+    for (a, b) in (0..count).tuple_combinations() {
+        let similarity = DeckSimilarity::between(&profiles[a], &profiles[b]).combined(weights);
+        linkage[a][b] = similarity;
+        linkage[b][a] = similarity;
+    }
+
+    let mut clusters: Vec<Option<Vec<usize>>> = (0..count).map(|index| Some(vec![index])).collect();
+
+    // The most similar other cluster of every cluster, so that the best pair can be found without scanning every pair
+    let nearest_of = |clusters: &[Option<Vec<usize>>], linkage: &[Vec<f64>], a: usize| -> Option<(usize, f64)> {
+        (0..clusters.len())
+            .filter(|b| *b != a && clusters[*b].is_some())
+            .map(|b| (b, linkage[a][b]))
+            .fold(None, |best, (b, value)| match best {
+                Some((_, best_value)) if best_value >= value => best,
+                _ => Some((b, value)),
+            })
+    };
+    let mut nearest = (0..count).map(|a| nearest_of(&clusters, &linkage, a)).collect_vec();
+
+    loop {
+        let best = nearest
+            .iter()
+            .enumerate()
+            .filter(|(a, _)| clusters[*a].is_some())
+            .filter_map(|(a, candidate)| candidate.map(|(b, value)| (a, b, value)))
+            .filter(|(_, _, value)| *value >= threshold)
+            .fold(None, |best: Option<(usize, usize, f64)>, candidate| match best {
+                Some((_, _, best_value)) if best_value >= candidate.2 => best,
+                _ => Some(candidate),
+            });
+
+        let (a, b) = match best {
+            Some((x, y, _)) => (x.min(y), x.max(y)),
+            None => break,
+        };
+
+        let merged = clusters[b].take().expect("merged cluster to exist");
+        let (a_len, b_len) = (clusters[a].as_ref().expect("merged cluster to exist").len() as f64, merged.len() as f64);
+        clusters[a].as_mut().expect("merged cluster to exist").extend(merged);
+
+        // Lance–Williams update for average linkage
+        for other in 0..count {
+            if other != a && clusters[other].is_some() {
+                let value = (a_len * linkage[a][other] + b_len * linkage[b][other]) / (a_len + b_len);
+                linkage[a][other] = value;
+                linkage[other][a] = value;
+            }
+        }
+
+        nearest[b] = None;
+        for other in 0..count {
+            if clusters[other].is_none() {
+                continue;
+            }
+            nearest[other] = match nearest[other] {
+                Some((target, _)) if other == a || target == a || target == b => nearest_of(&clusters, &linkage, other),
+                Some((_, value)) if linkage[other][a] > value => Some((a, linkage[other][a])),
+                current => current,
+            };
+        }
+    }
+
+    clusters
+        .into_iter()
+        .flatten()
+        .map(|members| label_cluster(members.into_iter().sorted().collect(), &profiles))
+        .sorted_by(|a, b| b.members.len().cmp(&a.members.len()).then(a.members.cmp(&b.members)))
+        .collect()
+}
+
+/// Create the [`Archetype`] of the given cluster, finding its dominant champions and regions.
+fn label_cluster(members: Vec<usize>, profiles: &[DeckProfile]) -> Archetype {
+    fn dominant<T: Clone + Eq + Hash + Ord>(sets: Vec<&HashSet<T>>) -> Vec<T> {
+        let mut counts: HashMap<&T, usize> = HashMap::new();
+        for item in sets.iter().flat_map(|set| set.iter()) {
+            *counts.entry(item).or_default() += 1;
+        }
+
+        counts
+            .into_iter()
+            .filter(|(_, count)| count * 2 >= sets.len())
+            .sorted_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)))
+            .map(|(item, _)| item.clone())
+            .collect()
+    }
+
+    let champions = dominant(members.iter().map(|index| &profiles[*index].champions).collect());
+    let regions = dominant(members.iter().map(|index| &profiles[*index].regions).collect());
+
+    Archetype {
+        members,
+        champions,
+        regions,
+    }
+}
+
+/// Generate the conventional name of a [`Deck`]: the names of its most copied champions, followed by the abbreviations of its regions, such as `Ezreal Draven (PZ/NX)`.
+///
+/// Champion names are taken from the [`CardIndex`], and region abbreviations from the [`LocalizedCardRegionIndex`], so the name is in the locale they were loaded in.
+///
+/// Up to two champions are named; regions are ordered by the first named champion belonging to them, then by the number of cards belonging to them.
+///
+/// Returns [`None`] if the deck has no champions and its regions could not be determined.
+pub fn archetype_name(deck: &Deck, cards: &CardIndex, regions: &LocalizedCardRegionIndex) -> Option<String> {
+    let champions = deck
+        .champions(cards)
+        .map(|card| (card.name.clone(), deck.copies_of(&card.code)))
+        .into_grouping_map()
+        .sum()
+        .into_iter()
+        .sorted_by(|(a_name, a_copies), (b_name, b_copies)| b_copies.cmp(a_copies).then(a_name.cmp(b_name)))
+        .take(NAMED_CHAMPIONS)
+        .map(|(name, _)| name)
+        .collect_vec();
+
+    let champion_regions = deck
+        .champions(cards)
+        .filter(|card| champions.contains(&card.name))
+        .sorted_by_key(|card| champions.iter().position(|name| *name == card.name))
+        .flat_map(|card| card.regions.iter().copied())
+        .collect_vec();
+
+    let region_cards = |region: &CardRegion| -> u32 {
+        deck.contents
+            .iter()
+            .filter(|(code, _)| code.to_card(cards).is_some_and(|card| card.regions.contains(region)))
+            .map(|(_, quantity)| quantity)
+            .sum()
+    };
+
+    let deck_regions = deck
+        .regions(cards, REGIONS_LIMIT)
+        .unwrap_or_default()
+        .into_iter()
+        .sorted_by_key(|region| (
+            champion_regions.iter().position(|r| r == region).unwrap_or(usize::MAX),
+            std::cmp::Reverse(region_cards(region)),
+            *region,
+        ))
+        .collect_vec();
+
+    format_name(champions, abbreviations(deck_regions.iter(), regions))
+}
+
+/// Get the localized abbreviations of the given regions, skipping the ones missing from the [`LocalizedCardRegionIndex`].
+fn abbreviations<'r>(deck_regions: impl Iterator<Item = &'r CardRegion>, regions: &LocalizedCardRegionIndex) -> Vec<String> {
+    deck_regions
+        .filter_map(|region| region.localized(regions))
+        .map(|localized| localized.abbreviation.clone())
+        .collect()
+}
+
+/// Join champion names and region abbreviations into an archetype name.
+fn format_name(champions: Vec<String>, abbreviations: Vec<String>) -> Option<String> {
+    match (champions.is_empty(), abbreviations.is_empty()) {
+        (true, true) => None,
+        (true, false) => Some(abbreviations.join("/")),
+        (false, true) => Some(champions.join(" ")),
+        (false, false) => Some(format!("{} ({})", champions.join(" "), abbreviations.join("/"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::corebundle::create_globalindexes_from_wd;
+    use crate::data::setbundle::create_cardindex_from_wd;
+    use crate::deck;
+
+    #[test]
+    fn jaccard_metrics() {
+        let a: Deck = deck!["01DE001": 3, "01DE002": 2];
+        let b: Deck = deck!["01DE001": 1, "01DE003": 2];
+
+        assert_eq!(weighted_jaccard(&a, &a), 1.0);
+        assert_eq!(weighted_jaccard(&a, &b), 1.0 / 7.0);
+        assert_eq!(weighted_jaccard(&deck![], &deck![]), 1.0);
+        assert_eq!(jaccard(&HashSet::from([1, 2]), &HashSet::from([2, 3])), 1.0 / 3.0);
+    }
+
+    #[test]
+    fn similarity_of_decks() {
+        let index = create_cardindex_from_wd();
+        let a: Deck = deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA");
+        let b: Deck = deck!("CQBQCBAJBUCAKCRYHKTADNIBAYBQSDQ2DQ3FEWACAECQVNQBAIBQSOK5AEAQGCIV");
+
+        let same = DeckSimilarity::between(&DeckProfile::of(&a, &index), &DeckProfile::of(&a, &index));
+        assert_eq!(same.combined(&SimilarityWeights::default()), 1.0);
+
+        let different = DeckSimilarity::between(&DeckProfile::of(&a, &index), &DeckProfile::of(&b, &index));
+        assert!(different.combined(&SimilarityWeights::default()) < 0.5);
+    }
+
+    #[test]
+    fn clustering() {
+        let index = create_cardindex_from_wd();
+        let a: Deck = deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA");
+        let b: Deck = deck!("CQBQCBAJBUCAKCRYHKTADNIBAYBQSDQ2DQ3FEWACAECQVNQBAIBQSOK5AEAQGCIV");
+
+        let mut variant = a.clone();
+        let (code, _) = variant.contents.iter().sorted().next().unwrap();
+        let code = code.clone();
+        variant.contents.remove(&code);
+
+        let decks = vec![a.clone(), b, variant];
+        let archetypes = cluster_decks(&decks, &index, &SimilarityWeights::default(), 0.6);
+
+        assert_eq!(archetypes.len(), 2);
+        assert_eq!(archetypes[0].members, vec![0, 2]);
+        assert_eq!(archetypes[1].members, vec![1]);
+
+        let champions: HashSet<CardCode> = a.champions(&index).map(|card| card.code.clone()).collect();
+        assert_eq!(archetypes[0].champions.iter().cloned().collect::<HashSet<CardCode>>(), champions);
+        assert!(!archetypes[0].regions.is_empty());
+    }
+
+    #[test]
+    fn clustering_matches_naive_linkage() {
+        let index = create_cardindex_from_wd();
+        let collectible = index.values().filter(|card| card.collectible).map(|card| card.code.clone()).sorted().take(40).collect_vec();
+
+        let decks = (0..60)
+            .map(|seed: usize| Deck {
+                contents: (0..4).map(|step| (collectible[(seed * 7 + step * step * 3) % collectible.len()].clone(), (seed + step) as u32 % 3 + 1)).collect(),
+            })
+            .collect_vec();
+        let weights = SimilarityWeights::default();
+
+        let profiles = decks.iter().map(|deck| DeckProfile::of(deck, &index)).collect_vec();
+        let linkage = |a: &[usize], b: &[usize]| -> f64 {
+            let sum: f64 = a.iter().cartesian_product(b.iter()).map(|(x, y)| DeckSimilarity::between(&profiles[*x], &profiles[*y]).combined(&weights)).sum();
+            sum / (a.len() * b.len()) as f64
+        };
+        let mut expected: Vec<Vec<usize>> = (0..decks.len()).map(|index| vec![index]).collect();
+        while let Some((a, b, _)) = (0..expected.len())
+            .tuple_combinations()
+            .map(|(a, b)| (a, b, linkage(&expected[a], &expected[b])))
+            .filter(|(_, _, value)| *value >= 0.3)
+            .fold(None, |best: Option<(usize, usize, f64)>, candidate| match best {
+                Some((_, _, best_value)) if best_value >= candidate.2 => best,
+                _ => Some(candidate),
+            })
+        {
+            let merged = expected.remove(b);
+            expected[a].extend(merged);
+            expected[a].sort();
+        }
+
+        let found = cluster_decks(&decks, &index, &weights, 0.3).into_iter().map(|archetype| archetype.members).sorted().collect_vec();
+        assert!(found.len() > 1 && found.len() < decks.len());
+        assert_eq!(found, expected.into_iter().sorted().collect_vec());
+    }
+
+    #[test]
+    fn name_of_deck() {
+        let index = create_cardindex_from_wd();
+        let globals = create_globalindexes_from_wd();
+
+        let deck: Deck = deck!["01FR009": 3, "01FR003": 1, "01DE002": 3];
+        assert_eq!(archetype_name(&deck, &index, &globals.regions), Some("Braum (FR/DE)".to_string()));
+
+        let no_champions: Deck = deck!["01DE002": 3];
+        assert_eq!(archetype_name(&no_champions, &index, &globals.regions), Some("DE".to_string()));
+
+        assert_eq!(archetype_name(&deck!["99XX999": 3], &index, &globals.regions), None);
+    }
+
+    #[test]
+    fn name_of_archetype() {
+        let index = create_cardindex_from_wd();
+        let globals = create_globalindexes_from_wd();
+
+        let decks = vec![deck!["01FR009": 3, "01DE002": 3], deck!["01FR009": 2, "01DE002": 3]];
+        let archetypes = cluster_decks(&decks, &index, &SimilarityWeights::default(), 0.5);
+
+        assert_eq!(archetypes.len(), 1);
+        assert!(archetypes[0].name(&index, &globals.regions).unwrap().starts_with("Braum ("));
+    }
+}
@@ -0,0 +1,343 @@
+//! Module defining [`DeckBuilder`], which edits a [`Deck`] one card at a time while tracking the constraints of a format.
+
+use crate::data::deckcode::deck::Deck;
+use crate::data::deckcode::legality::{LegalityReport, LegalityViolation};
+use crate::data::deckcode::rules::FormatRules;
+use crate::data::setbundle::card::CardIndex;
+use crate::data::setbundle::code::CardCode;
+use crate::data::setbundle::region::CardRegion;
+use crate::data::setbundle::supertype::CardSupertype;
+use itertools::Itertools;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+/// A [`Deck`] being edited, which reports the constraints of a format left to respect after every change.
+///
+/// The violations caused by single cards and the regions of the deck are cached, and only recomputed when the cards they depend on change.
+#[derive(Clone, Debug)]
+pub struct DeckBuilder<'c> {
+    /// The cards the deck may contain.
+    cards: &'c CardIndex,
+    /// The rules of the format the deck is built for.
+    rules: FormatRules,
+    /// The deck being built.
+    deck: Deck,
+    /// The number of copies of champions in the deck.
+    champions: u32,
+    /// The violations caused by each card of the deck by itself.
+    card_violations: BTreeMap<CardCode, Vec<LegalityViolation>>,
+    /// The regions chosen for the current cards of the deck, or [`None`] if they do not fit in the regions limit.
+    regions: Option<HashSet<CardRegion>>,
+}
+
+impl<'c> DeckBuilder<'c> {
+    /// Start building an empty deck for the format with the given [`FormatRules`].
+    pub fn new(cards: &'c CardIndex, rules: FormatRules) -> Self {
+        Self::from_deck(cards, rules, Deck { contents: Default::default() })
+    }
+
+    /// Continue building the given [`Deck`] for the format with the given [`FormatRules`].
+    pub fn from_deck(cards: &'c CardIndex, rules: FormatRules, deck: Deck) -> Self {
+        let champions = deck.champions_count(cards);
+        let card_violations = deck.contents
+            .keys()
+            .map(|code| (code.clone(), rules.card_violations(code, cards)))
+            .collect();
+        let regions = deck.regions(cards, rules.regions_limit);
+
+        Self { cards, rules, deck, champions, card_violations, regions }
+    }
+
+    /// The deck built so far.
+    pub fn deck(&self) -> &Deck {
+        &self.deck
+    }
+
+    /// Stop building, returning the deck built so far.
+    pub fn into_deck(self) -> Deck {
+        self.deck
+    }
+
+    /// The rules of the format the deck is built for.
+    pub fn rules(&self) -> &FormatRules {
+        &self.rules
+    }
+
+    /// Add the given number of copies of a card to the deck.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use patched_porobot::data::deckcode::builder::DeckBuilder;
+    /// use patched_porobot::data::deckcode::rules::FormatRules;
+    /// use patched_porobot::data::setbundle::code::CardCode;
+    /// use patched_porobot::data::setbundle::create_cardindex_from_wd;
+    ///
+    /// let index = create_cardindex_from_wd();
+    /// let mut builder = DeckBuilder::new(&index, FormatRules::eternal());
+    ///
+    /// let status = builder.add(&CardCode::from("01DE012".to_string()), 2).unwrap();
+    /// assert_eq!(status.cards_left, 38);
+    /// assert_eq!(status.copies_left, 1);
+    /// assert_eq!(status.champion_slots_left, Some(4));
+    /// assert!(!status.report.is_legal());
+    /// ```
+    pub fn add(&mut self, code: &CardCode, copies: u32) -> Result<BuilderStatus, BuilderError> {
+        let current = self.deck.copies_of(code);
+        match current.checked_add(copies) {
+            Some(quantity) => self.set_quantity(code, quantity),
+            None => Err(BuilderError::TooManyCopies { card: code.clone(), copies: current, added: copies }),
+        }
+    }
+
+    /// Remove the given number of copies of a card from the deck.
+    pub fn remove(&mut self, code: &CardCode, copies: u32) -> Result<BuilderStatus, BuilderError> {
+        let current = self.deck.copies_of(code);
+        match current.checked_sub(copies) {
+            Some(quantity) => self.set_quantity(code, quantity),
+            None => Err(BuilderError::NotEnoughCopies { card: code.clone(), copies: current, removed: copies }),
+        }
+    }
+
+    /// Set the number of copies of a card in the deck, removing it if the quantity is zero.
+    ///
+    /// Only adding copies requires the card to be in the [`CardIndex`] of the builder, so that unknown cards can still be removed from the deck.
+    /// Fails with [`BuilderError::TooManyCopies`] if the number of cards in the deck would not fit in a [`u32`].
+    pub fn set_quantity(&mut self, code: &CardCode, quantity: u32) -> Result<BuilderStatus, BuilderError> {
+        let previous = self.deck.copies_of(code);
+        let card = code.to_card(self.cards);
+
+        if card.is_none() && quantity > previous {
+            return Err(BuilderError::UnknownCard(code.clone()));
+        }
+
+        if (self.deck.card_count() - previous).checked_add(quantity).is_none() {
+            return Err(BuilderError::TooManyCopies { card: code.clone(), copies: previous, added: quantity - previous });
+        }
+
+        if card.is_some_and(|card| card.supertype == CardSupertype::Champion) {
+            self.champions = self.champions - previous + quantity;
+        }
+
+        let was_present = previous > 0;
+        match quantity {
+            0 => {
+                self.deck.contents.remove(code);
+                self.card_violations.remove(code);
+            }
+            _ => {
+                self.deck.contents.insert(code.clone(), quantity);
+                if !was_present {
+                    self.card_violations.insert(code.clone(), self.rules.card_violations(code, self.cards));
+                }
+            }
+        }
+
+        if was_present != (quantity > 0) {
+            self.regions = self.deck.regions(self.cards, self.rules.regions_limit);
+        }
+
+        Ok(self.status_of(code))
+    }
+
+    /// Get the current state of the deck, without reference to a specific card.
+    pub fn status(&self) -> BuilderStatus {
+        BuilderStatus {
+            card_count: self.deck.card_count(),
+            cards_left: self.rules.deck_size.saturating_sub(self.deck.card_count()),
+            copies_left: self.rules.copies_limit,
+            champion_slots_left: self.champion_slots_left(),
+            regions: self.regions.clone(),
+            report: self.report(),
+        }
+    }
+
+    /// Get the current state of the deck, reporting the copies left of the given card.
+    fn status_of(&self, code: &CardCode) -> BuilderStatus {
+        BuilderStatus {
+            copies_left: self.rules.copies_limit.saturating_sub(self.deck.copies_of(code)),
+            ..self.status()
+        }
+    }
+
+    /// The number of champion copies which can still be added to the deck, or [`None`] if the format has no limit.
+    fn champion_slots_left(&self) -> Option<u32> {
+        self.rules.champions_limit.map(|limit| limit.saturating_sub(self.champions))
+    }
+
+    /// Build the [`LegalityReport`] of the deck from the cached state, equal to the one returned by [`Deck::check`].
+    fn report(&self) -> LegalityReport {
+        let mut violations = vec![];
+
+        let card_count = self.deck.card_count();
+        if card_count != self.rules.deck_size {
+            violations.push(LegalityViolation::CardCount { expected: self.rules.deck_size, found: card_count });
+        }
+
+        for (code, copies) in self.deck.contents.iter().sorted() {
+            if *copies > self.rules.copies_limit {
+                violations.push(LegalityViolation::TooManyCopies { card: code.clone(), copies: *copies, limit: self.rules.copies_limit });
+            }
+        }
+
+        if let Some(limit) = self.rules.champions_limit {
+            if self.champions > limit {
+                violations.push(LegalityViolation::TooManyChampions { found: self.champions, limit });
+            }
+        }
+
+        violations.extend(self.card_violations.values().flatten().cloned());
+
+        if self.regions.is_none() {
+            violations.extend(self.deck.regions_violations(self.cards, self.rules.regions_limit));
+        }
+
+        let regions = match violations.is_empty() {
+            true => self.regions.clone(),
+            false => None,
+        };
+
+        LegalityReport { violations, regions }
+    }
+}
+
+/// The state of a [`Deck`] reported by a [`DeckBuilder`] after a change.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BuilderStatus {
+    /// The number of cards in the deck.
+    pub card_count: u32,
+    /// The number of cards which still have to be added to reach the deck size of the format.
+    pub cards_left: u32,
+    /// The number of copies of the changed card which can still be added, or the copies limit if no card was changed.
+    pub copies_left: u32,
+    /// The number of champion copies which can still be added, or [`None`] if the format has no champions limit.
+    pub champion_slots_left: Option<u32>,
+    /// The regions used by the deck, or [`None`] if the cards of the deck do not fit in the regions limit.
+    pub regions: Option<HashSet<CardRegion>>,
+    /// The legality of the deck in the format it is built for.
+    pub report: LegalityReport,
+}
+
+/// An error occoured while changing the [`Deck`] of a [`DeckBuilder`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BuilderError {
+    /// The card is not in the [`CardIndex`] of the builder.
+    UnknownCard(CardCode),
+    /// The deck has less copies of a card than requested to remove.
+    NotEnoughCopies {
+        /// The card with not enough copies.
+        card: CardCode,
+        /// The number of copies in the deck.
+        copies: u32,
+        /// The number of copies requested to remove.
+        removed: u32,
+    },
+    /// The deck would have more cards than can be counted.
+    TooManyCopies {
+        /// The card with too many copies.
+        card: CardCode,
+        /// The number of copies in the deck.
+        copies: u32,
+        /// The number of copies requested to add.
+        added: u32,
+    },
+}
+
+impl Display for BuilderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuilderError::UnknownCard(card) => write!(f, "{} is not a known card", card.full),
+            BuilderError::NotEnoughCopies { card, copies, removed } => write!(f, "the deck has {} copies of {}, but {} should be removed", copies, card.full, removed),
+            BuilderError::TooManyCopies { card, copies, added } => write!(f, "the deck has {} copies of {}, and {} more cannot be added", copies, card.full, added),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::setbundle::create_cardindex_from_wd;
+    use crate::deck;
+
+    fn code(code: &str) -> CardCode {
+        CardCode::from(code.to_string())
+    }
+
+    #[test]
+    fn status_matches_check() {
+        let index = create_cardindex_from_wd();
+        let mut builder = DeckBuilder::new(&index, FormatRules::standard());
+
+        let steps: Vec<(&str, i64)> = vec![("01DE012", 3), ("01DE045", 4), ("01FR009", 2), ("01DE002", 3), ("01DE045", -2), ("01FR009", -2)];
+        for (card, delta) in steps {
+            let status = match delta > 0 {
+                true => builder.add(&code(card), delta as u32),
+                false => builder.remove(&code(card), (-delta) as u32),
+            }
+            .unwrap();
+
+            assert_eq!(status.report, builder.deck().check(&index, &FormatRules::standard()));
+            assert_eq!(status.regions, builder.deck().regions(&index, 2));
+        }
+
+        assert_eq!(builder.deck(), &deck!["01DE012": 3, "01DE045": 2, "01DE002": 3]);
+    }
+
+    #[test]
+    fn allowances() {
+        let index = create_cardindex_from_wd();
+        let mut builder = DeckBuilder::new(&index, FormatRules::standard());
+
+        let status = builder.set_quantity(&code("01DE045"), 3).unwrap();
+        assert_eq!(status.copies_left, 0);
+        assert_eq!(status.champion_slots_left, Some(3));
+        assert_eq!(status.regions, Some(HashSet::from([CardRegion::Demacia])));
+
+        let status = builder.add(&code("01DE045"), 1).unwrap();
+        assert!(status.report.violations.contains(&LegalityViolation::TooManyCopies { card: code("01DE045"), copies: 4, limit: 3 }));
+
+        let status = builder.set_quantity(&code("01DE045"), 0).unwrap();
+        assert_eq!(status.card_count, 0);
+        assert_eq!(status.champion_slots_left, Some(6));
+        assert_eq!(builder.status().report, deck![].check(&index, &FormatRules::standard()));
+    }
+
+    #[test]
+    fn errors() {
+        let index = create_cardindex_from_wd();
+        let mut builder = DeckBuilder::from_deck(&index, FormatRules::eternal(), deck!["01DE002": 1]);
+
+        assert_eq!(builder.add(&code("99XX999"), 1), Err(BuilderError::UnknownCard(code("99XX999"))));
+        assert_eq!(builder.remove(&code("01DE002"), 2), Err(BuilderError::NotEnoughCopies { card: code("01DE002"), copies: 1, removed: 2 }));
+        assert_eq!(builder.add(&code("01DE002"), u32::MAX), Err(BuilderError::TooManyCopies { card: code("01DE002"), copies: 1, added: u32::MAX }));
+        assert_eq!(builder.set_quantity(&code("01DE045"), u32::MAX), Err(BuilderError::TooManyCopies { card: code("01DE045"), copies: 0, added: u32::MAX }));
+        assert_eq!(builder.into_deck(), deck!["01DE002": 1]);
+    }
+
+    #[test]
+    fn remove_unknown() {
+        let index = create_cardindex_from_wd();
+        let mut builder = DeckBuilder::from_deck(&index, FormatRules::eternal(), deck!["01DE002": 1, "99XX999": 2]);
+        assert!(builder.status().report.violations.contains(&LegalityViolation::UnknownCard(code("99XX999"))));
+
+        let status = builder.remove(&code("99XX999"), 1).unwrap();
+        assert_eq!(status.card_count, 2);
+
+        let status = builder.set_quantity(&code("99XX999"), 0).unwrap();
+        assert_eq!(status.report, deck!["01DE002": 1].check(&index, &FormatRules::eternal()));
+        assert_eq!(builder.add(&code("99XX999"), 1), Err(BuilderError::UnknownCard(code("99XX999"))));
+    }
+
+    #[test]
+    fn status_matches_check_origins() {
+        let index = create_cardindex_from_wd();
+        let mut builder = DeckBuilder::new(&index, FormatRules::eternal());
+
+        for card in ["06RU001", "01DE002", "01FR003", "01IO003"] {
+            let status = builder.add(&code(card), 1).unwrap();
+            assert_eq!(status.report, builder.deck().check(&index, &FormatRules::eternal()));
+        }
+
+        assert!(!builder.status().report.violations.iter().any(|v| matches!(v, LegalityViolation::OriginRequirementUnmet { .. })));
+    }
+}
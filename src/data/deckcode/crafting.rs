@@ -0,0 +1,145 @@
+//! Module defining the [`CraftingCost`] of a [`Deck`], in wildcards and shards.
+
+use crate::data::deckcode::deck::Deck;
+use crate::data::setbundle::card::CardIndex;
+use crate::data::setbundle::code::CardCode;
+use crate::data::setbundle::rarity::CardRarity;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+/// The cards owned by a player, with the number of copies of each of them.
+pub type Collection = HashMap<CardCode, u32>;
+
+/// The resources required to craft the cards of a [`Deck`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CraftingCost {
+    /// The number of wildcards of each [`CardRarity`] required, or equivalently, the number of cards of each rarity to craft.
+    ///
+    /// Counts saturate at [`u32::MAX`].
+    pub wildcards: HashMap<CardRarity, u32>,
+
+    /// The total number of shards required to craft every card.
+    ///
+    /// As decks may contain any number of copies of a card, it is a [`u64`], which cannot overflow for any deck with less than 2³² cards.
+    pub shards: u64,
+
+    /// The cards to craft with their number of copies, sorted by card code.
+    pub missing: Vec<(CardCode, u32)>,
+
+    /// The cards which cannot be crafted with their number of copies, sorted by card code.
+    ///
+    /// Contains cards missing from the [`CardIndex`] and cards without a craftable [`CardRarity`].
+    pub uncraftable: Vec<(CardCode, u32)>,
+}
+
+impl CraftingCost {
+    /// Compute the cost of crafting every card of the given [`Deck`].
+    pub fn of(deck: &Deck, cards: &CardIndex) -> Self {
+        Self::of_missing(deck, cards, &Collection::new())
+    }
+
+    /// Compute the cost of crafting the cards of the given [`Deck`] which are not part of the given [`Collection`].
+    pub fn of_missing(deck: &Deck, cards: &CardIndex, owned: &Collection) -> Self {
+        let mut cost = Self {
+            wildcards: HashMap::new(),
+            shards: 0,
+            missing: vec![],
+            uncraftable: vec![],
+        };
+
+        for (code, quantity) in deck.contents.iter().sorted() {
+            let needed = quantity.saturating_sub(owned.get(code).copied().unwrap_or(0));
+            if needed == 0 {
+                continue;
+            }
+
+            let rarity = code.to_card(cards).map(|card| card.crafting_rarity());
+            let shards = rarity.and_then(|rarity| rarity.shard_cost());
+
+            match (rarity, shards) {
+                (Some(rarity), Some(shards)) => {
+                    let wildcards = cost.wildcards.entry(rarity).or_default();
+                    *wildcards = wildcards.saturating_add(needed);
+                    cost.shards = cost.shards.saturating_add(u64::from(shards) * u64::from(needed));
+                    cost.missing.push((code.clone(), needed));
+                }
+                _ => cost.uncraftable.push((code.clone(), needed)),
+            }
+        }
+
+        cost
+    }
+
+    /// Get the number of wildcards of the given [`CardRarity`] required.
+    pub fn wildcards_of(&self, rarity: CardRarity) -> u32 {
+        self.wildcards.get(&rarity).copied().unwrap_or(0)
+    }
+
+    /// Whether nothing has to be crafted.
+    pub fn is_free(&self) -> bool {
+        self.missing.is_empty() && self.uncraftable.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::setbundle::create_cardindex_from_wd;
+    use crate::deck;
+
+    #[test]
+    fn cost_of_deck() {
+        let index = create_cardindex_from_wd();
+        let deck: Deck = deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA");
+        let cost = CraftingCost::of(&deck, &index);
+
+        let wildcards: u32 = cost.wildcards.values().sum();
+        assert_eq!(wildcards, 40);
+        assert_eq!(cost.wildcards_of(CardRarity::Champion), deck.champions_count(&index));
+        assert!(cost.uncraftable.is_empty());
+
+        assert_eq!(cost.wildcards, HashMap::from([
+            (CardRarity::Common, 12),
+            (CardRarity::Rare, 16),
+            (CardRarity::Epic, 6),
+            (CardRarity::Champion, 6),
+        ]));
+        assert_eq!(cost.shards, 31200);
+    }
+
+    #[test]
+    fn cost_of_huge_quantities() {
+        let index = create_cardindex_from_wd();
+        let deck: Deck = deck!["01FR009": u32::MAX, "01DE045": u32::MAX];
+        let cost = CraftingCost::of(&deck, &index);
+
+        assert_eq!(cost.wildcards_of(CardRarity::Champion), u32::MAX);
+        assert_eq!(cost.shards, 2 * 3000 * u64::from(u32::MAX));
+    }
+
+    #[test]
+    fn cost_with_collection() {
+        let index = create_cardindex_from_wd();
+        let deck: Deck = deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA");
+
+        let everything = deck.contents.clone();
+        assert!(CraftingCost::of_missing(&deck, &index, &everything).is_free());
+
+        let (code, _) = deck.contents.iter().sorted().next().unwrap();
+        let mut partial = everything.clone();
+        partial.insert(code.clone(), 0);
+
+        let cost = CraftingCost::of_missing(&deck, &index, &partial);
+        assert_eq!(cost.missing, vec![(code.clone(), deck.copies_of(code))]);
+    }
+
+    #[test]
+    fn cost_of_unknown() {
+        let index = create_cardindex_from_wd();
+        let deck: Deck = deck!["99XX999": 3];
+        let cost = CraftingCost::of(&deck, &index);
+
+        assert_eq!(cost.shards, 0);
+        assert_eq!(cost.uncraftable, vec![(CardCode::from("99XX999".to_string()), 3)]);
+    }
+}
@@ -1,19 +1,33 @@
 //! Module defining the [`Deck`] struct and its serialization methods and results.
 
 use super::format::DeckCodeFormat;
+use crate::data::corebundle::region::LocalizedCardRegionIndex;
+use crate::data::deckcode::archetype::archetype_name;
+use crate::data::deckcode::crafting::{Collection, CraftingCost};
+use crate::data::deckcode::decklist::{emit_decklist, parse_decklist, DecklistResult};
+use crate::data::deckcode::diff::{DeckDiff, DeckPatchError};
+use crate::data::deckcode::legality::{LegalityReport, LegalityViolation};
+use crate::data::deckcode::origins::origin_of;
+use crate::data::deckcode::probability::{DrawQuery, DrawRules};
+use crate::data::deckcode::regions::RegionAssignment;
+use crate::data::deckcode::registry::registry;
+use crate::data::deckcode::rules::FormatRules;
+use crate::data::deckcode::stats::DeckStats;
 use crate::data::deckcode::version::{DeckCodeVersion, DeckCodeVersioned};
 use crate::data::setbundle::card::{Card, CardIndex};
 use crate::data::setbundle::code::CardCode;
 use crate::data::setbundle::region::CardRegion;
-use crate::data::setbundle::set::CardSet;
 use itertools::Itertools;
 use std::collections::{HashMap, HashSet};
-use std::io::{Cursor, Read, Write};
-use varint_rs::{VarintReader, VarintWriter};
+use std::fmt::{Display, Formatter};
+use std::io::{Cursor, Write};
+use varint_rs::VarintWriter;
 use crate::data::setbundle::format::CardFormat;
 use crate::data::setbundle::supertype::CardSupertype;
 
 /// A unshuffled Legends of Runeterra card deck.
+///
+/// See the [`representation`](crate::data::deckcode::representation) module for how it is (de)serialized with [`serde`].
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Deck {
     /// The contents of the deck, represented as a [`HashMap`] mapping [`CardCode`]s to the number of inserted cards.
@@ -34,18 +48,16 @@ impl Deck {
     }
 
     /// [Read] the header byte into a [format](DeckCodeFormat) and [version](DeckCodeVersion) tuple.
-    fn read_header<R: Read>(
-        reader: &mut R,
+    fn read_header(
+        reader: &mut DeckCodeReader,
     ) -> DeckDecodingResult<(DeckCodeFormat, DeckCodeVersion)> {
-        let mut format_version: [u8; 1] = [0; 1];
-        reader
-            .read_exact(&mut format_version)
-            .map_err(DeckDecodingError::Read)?;
+        let offset = reader.offset;
+        let format_version = reader.read_byte(DeckCodeExpectation::FormatAndVersion)?;
 
-        let format = DeckCodeFormat::try_from(format_version[0] >> 4)
-            .map_err(|_| DeckDecodingError::UnknownFormat)?;
-        let version = DeckCodeVersion::try_from(format_version[0] & 0xF)
-            .map_err(|_| DeckDecodingError::UnknownVersion)?;
+        let format = DeckCodeFormat::try_from(format_version >> 4)
+            .map_err(|_| reader.error_at(offset, DeckCodeExpectation::FormatAndVersion, DeckDecodingProblem::UnknownFormat(format_version >> 4)))?;
+        let version = DeckCodeVersion::try_from(format_version & 0xF)
+            .map_err(|_| reader.error_at(offset, DeckCodeExpectation::FormatAndVersion, DeckDecodingProblem::UnknownVersion(format_version & 0xF)))?;
 
         Ok((format, version))
     }
@@ -67,16 +79,18 @@ impl Deck {
     }
 
     /// [Read] a [`Deck`] using the [`F1`](DeckCodeFormat::F1) format.
-    fn read_f1_body<R: Read>(reader: &mut R) -> DeckDecodingResult<Self> {
+    fn read_f1_body(reader: &mut DeckCodeReader) -> DeckDecodingResult<Self> {
         // Create the deck's cards container
         let mut contents = HashMap::<CardCode, u32>::new();
 
         // Read the standard body
         for quantity in (1..=3).rev() {
+            reader.section = DeckCodeSection::Supergroup(quantity);
             Self::read_f1_supergroup(reader, &mut contents, quantity)?;
         }
 
         // Read the extra body
+        reader.section = DeckCodeSection::Extras;
         Self::read_f1_extra(reader, &mut contents)?;
 
         // Create and return the deck
@@ -97,6 +111,7 @@ impl Deck {
                 3 => triplets.push(code),
                 2 => twins.push(code),
                 1 => singletons.push(code),
+                0 => return Err(DeckEncodingError::ZeroQuantity(code.clone())),
                 _ => extra.push((code, *quantity)),
             }
         }
@@ -113,13 +128,13 @@ impl Deck {
     }
 
     /// [Read] the **groups** of a single supergroup.
-    fn read_f1_supergroup<R: Read>(
-        reader: &mut R,
+    fn read_f1_supergroup(
+        reader: &mut DeckCodeReader,
         contents: &mut HashMap<CardCode, u32>,
         quantity: u32,
     ) -> DeckDecodingResult<()> {
         // Read the number of groups in the supergroup
-        let len = reader.read_u32_varint().map_err(DeckDecodingError::Read)?;
+        let len = reader.read_varint(DeckCodeExpectation::GroupCount)?;
 
         // Read all groups
         for _ in 0..len {
@@ -129,35 +144,21 @@ impl Deck {
         Ok(())
     }
 
-    /// Given a slice of [`CardCode`]s, group them by set and region.
-    fn f1_group_cards<'cc>(
-        codes: &[&'cc CardCode],
-    ) -> HashMap<(&'cc str, &'cc str), Vec<&'cc CardCode>> {
-        // Create the hashmap accumulating groups of cards
-        // It has the tuple (set, region) as key
-        let mut groups = HashMap::new();
-
-        // Insert all cards into the various groups
-        for card in codes {
-            // Determine the key tuple
-            let key = (card.set(), card.region());
-
-            // Create or insert groups
-            match groups.get(&key) {
-                None => {
-                    let group = vec![*card];
-                    groups.insert(key, group);
-                }
-                Some(_) => {
-                    // FIXME: there's a better version for sure
-                    let mut group = groups.remove(&key).unwrap();
-                    group.push(*card);
-                    groups.insert(key, group);
-                }
-            }
-        }
-
-        groups
+    /// Given a slice of [`CardCode`]s, group them by set and region, in the same order as Riot's reference encoder.
+    ///
+    /// Cards inside each group are sorted by card code, and groups are sorted first by ascending length, then by the code of their first card.
+    fn f1_group_cards<'cc>(codes: &[&'cc CardCode]) -> Vec<Vec<&'cc CardCode>> {
+        codes
+            .iter()
+            .copied()
+            .sorted()
+            .group_by(|card| (card.set(), card.region()))
+            .into_iter()
+            .map(|(_key, group)| group.collect_vec())
+            .sorted_by(|a_group, b_group| {
+                a_group.len().cmp(&b_group.len()).then(a_group[0].cmp(b_group[0]))
+            })
+            .collect()
     }
 
     /// [Write] the **groups** of a single supergroup.
@@ -165,7 +166,7 @@ impl Deck {
         writer: &mut W,
         supergroup: &[&CardCode],
     ) -> DeckEncodingResult<()> {
-        // Arrange cards into groups
+        // Arrange cards into sorted groups
         let groups = Self::f1_group_cards(supergroup);
 
         // Determine the number of groups in the supergroup
@@ -177,36 +178,23 @@ impl Deck {
             .write_u32_varint(len)
             .map_err(DeckEncodingError::Write)?;
 
-        // Sort first by ascending group length, then by key
-        let groups = groups
-            .into_iter()
-            .sorted_by(|(a_key, a_group), (b_key, b_group)| {
-                a_group.len().cmp(&b_group.len()).then(a_key.cmp(b_key))
-            });
-
         // Write all groups
-        for ((set, region), group) in groups {
-            Self::write_f1_group(writer, &group, set, region)?;
+        for group in groups {
+            Self::write_f1_group(writer, &group)?;
         }
 
         Ok(())
     }
 
     /// [Read] the **cards** of a single group.
-    fn read_f1_group<R: Read>(
-        reader: &mut R,
+    fn read_f1_group(
+        reader: &mut DeckCodeReader,
         contents: &mut HashMap<CardCode, u32>,
         quantity: u32,
     ) -> DeckDecodingResult<()> {
-        let card_count = reader.read_u32_varint().map_err(DeckDecodingError::Read)?;
+        let card_count = reader.read_varint(DeckCodeExpectation::CardCount)?;
 
-        let set = reader.read_u32_varint().map_err(DeckDecodingError::Read)?;
-        let set = format!("{:02}", &set);
-
-        let region = reader.read_u32_varint().map_err(DeckDecodingError::Read)?;
-        let region = CardRegion::from(region)
-            .to_code()
-            .ok_or(DeckDecodingError::UnknownRegion)?;
+        let (set, region) = Self::read_f1_set_region(reader)?;
 
         for _card in 0..card_count {
             Self::read_f1_standard_card(reader, contents, quantity, &set, &region)?;
@@ -215,13 +203,41 @@ impl Deck {
         Ok(())
     }
 
+    /// [Read] the set and region ids of a group or of a single card, and convert them to short codes via the [`CardIdRegistry`](crate::data::deckcode::registry::CardIdRegistry).
+    fn read_f1_set_region(reader: &mut DeckCodeReader) -> DeckDecodingResult<(String, String)> {
+        let set_offset = reader.offset;
+        let set = reader.read_varint(DeckCodeExpectation::SetId)?;
+
+        let region_offset = reader.offset;
+        let region = reader.read_varint(DeckCodeExpectation::RegionId)?;
+
+        let registry = registry();
+
+        let set = registry
+            .set_by_id(set)
+            .ok_or_else(|| reader.error_at(set_offset, DeckCodeExpectation::SetId, DeckDecodingProblem::UnknownSet(set)))?
+            .code
+            .clone();
+
+        let region = registry
+            .region_by_id(region)
+            .ok_or_else(|| reader.error_at(region_offset, DeckCodeExpectation::RegionId, DeckDecodingProblem::UnknownRegion(region)))?
+            .code
+            .clone();
+
+        Ok((set, region))
+    }
+
     /// [Write] the **cards** of a single group.
+    ///
+    /// All cards in the group are expected to have the same set and region, and to be already sorted.
     fn write_f1_group<W: Write>(
         writer: &mut W,
         group: &[&CardCode],
-        set: &str,
-        region: &str,
     ) -> DeckEncodingResult<()> {
+        let set = group[0].set();
+        let region = group[0].region();
+
         let len: u32 = group
             .len()
             .try_into()
@@ -230,20 +246,21 @@ impl Deck {
             .write_u32_varint(len)
             .map_err(DeckEncodingError::Write)?;
 
-        let set: u32 = set.parse()
-            .map_err(|_| DeckEncodingError::UnknownSet)?;
+        let (set, region) = {
+            let registry = registry();
+            let set = registry.set_by_code(set).ok_or(DeckEncodingError::UnknownSet)?.id;
+            let region = registry.region_by_code(region).ok_or(DeckEncodingError::UnknownRegion)?.id;
+            (set, region)
+        };
+
         writer
             .write_u32_varint(set)
             .map_err(DeckEncodingError::Write)?;
-
-        let region: u32 = CardRegion::from_code(region)
-            .try_into()
-            .map_err(|_| DeckEncodingError::UnknownRegion)?;
         writer
             .write_u32_varint(region)
             .map_err(DeckEncodingError::Write)?;
 
-        for card in group.iter().sorted() {
+        for card in group {
             Self::write_f1_standard_card(writer, card.card())?;
         }
 
@@ -251,16 +268,38 @@ impl Deck {
     }
 
     /// [Read] **a single card**.
-    fn read_f1_standard_card<R: Read>(
-        reader: &mut R,
+    fn read_f1_standard_card(
+        reader: &mut DeckCodeReader,
         contents: &mut HashMap<CardCode, u32>,
         quantity: u32,
         set: &str,
         region: &str,
     ) -> DeckDecodingResult<()> {
-        let card = reader.read_u32_varint().map_err(DeckDecodingError::Read)?;
+        let offset = reader.offset;
+        let card = reader.read_varint(DeckCodeExpectation::CardNumber)?;
 
         let code = CardCode::from_s_r_c(set, region, card);
+        Self::insert_f1_card(reader, contents, offset, code, quantity)
+    }
+
+    /// Insert a card read from a deck code into the deck contents, checking for duplicates in strict mode.
+    ///
+    /// In lenient mode, cards with zero copies are skipped, as they cannot be encoded again.
+    fn insert_f1_card(
+        reader: &DeckCodeReader,
+        contents: &mut HashMap<CardCode, u32>,
+        offset: usize,
+        code: CardCode,
+        quantity: u32,
+    ) -> DeckDecodingResult<()> {
+        if reader.strict && contents.contains_key(&code) {
+            return Err(reader.error_at(offset, DeckCodeExpectation::CardNumber, DeckDecodingProblem::DuplicateCard(code)));
+        }
+
+        if quantity == 0 {
+            return Ok(());
+        }
+
         contents.insert(code, quantity);
 
         Ok(())
@@ -279,22 +318,41 @@ impl Deck {
     }
 
     /// [Read] the **extra segment** of the deck code.
-    fn read_f1_extra<R: Read>(
-        reader: &mut R,
+    ///
+    /// In lenient mode, reading stops silently at the first card that cannot be read; in strict mode, the whole remainder of the deck code must consist of valid cards.
+    fn read_f1_extra(
+        reader: &mut DeckCodeReader,
         contents: &mut HashMap<CardCode, u32>,
     ) -> DeckDecodingResult<()> {
-        // While the cursor has still some bytes left...
-        while Self::read_f1_extra_card(reader, contents).is_ok() {}
+        if !reader.strict {
+            // While the cursor has still some bytes left...
+            while Self::read_f1_extra_card(reader, contents).is_ok() {}
+            return Ok(());
+        }
+
+        while !reader.is_exhausted() {
+            let offset = reader.offset;
+
+            Self::read_f1_extra_card(reader, contents).map_err(|err| match err {
+                // A card cut in half is not a truncated deck code, but garbage at its end
+                DeckDecodingError::Invalid(DeckDecodingDiagnostic { expected, problem: DeckDecodingProblem::UnexpectedEnd, .. }) => {
+                    reader.error_at(offset, expected, DeckDecodingProblem::TrailingData)
+                }
+                _ => err,
+            })?;
+        }
 
         Ok(())
     }
 
     /// [Write] the **extra segment** of the deck code.
+    ///
+    /// Cards are written sorted by card code, like Riot's reference encoder does.
     fn write_f1_extra<W: Write>(
         writer: &mut W,
         codes: Vec<(&CardCode, u32)>,
     ) -> DeckEncodingResult<()> {
-        for (code, quantity) in codes.iter().sorted() {
+        for (code, quantity) in codes.iter().sorted_by_key(|(code, _)| *code) {
             Self::write_f1_extra_card(writer, code, *quantity)?;
         }
 
@@ -302,28 +360,24 @@ impl Deck {
     }
 
     /// [Read] **a single card** with a **non-standard quantity**.
-    fn read_f1_extra_card<R: Read>(
-        reader: &mut R,
+    fn read_f1_extra_card(
+        reader: &mut DeckCodeReader,
         contents: &mut HashMap<CardCode, u32>,
     ) -> DeckDecodingResult<()> {
-        let quantity = reader.read_u32_varint().map_err(DeckDecodingError::Read)?;
+        let quantity_offset = reader.offset;
+        let quantity = reader.read_varint(DeckCodeExpectation::Quantity)?;
 
-        let set = reader.read_u32_varint().map_err(DeckDecodingError::Read)?;
-        let set = CardSet::from(set)
-            .to_code()
-            .ok_or(DeckDecodingError::UnknownSet)?;
+        if reader.strict && quantity == 0 {
+            return Err(reader.error_at(quantity_offset, DeckCodeExpectation::Quantity, DeckDecodingProblem::ZeroQuantity));
+        }
 
-        let region = reader.read_u32_varint().map_err(DeckDecodingError::Read)?;
-        let region = CardRegion::from(region)
-            .to_code()
-            .ok_or(DeckDecodingError::UnknownRegion)?;
+        let (set, region) = Self::read_f1_set_region(reader)?;
 
-        let card = reader.read_u32_varint().map_err(DeckDecodingError::Read)?;
+        let card_offset = reader.offset;
+        let card = reader.read_varint(DeckCodeExpectation::CardNumber)?;
 
         let code = CardCode::from_s_r_c(&set, &region, card);
-        contents.insert(code, quantity);
-
-        Ok(())
+        Self::insert_f1_card(reader, contents, card_offset, code, quantity)
     }
 
     /// [Write] **a single card** with a **non-standard quantity**.
@@ -336,16 +390,16 @@ impl Deck {
             .write_u32_varint(quantity)
             .map_err(DeckEncodingError::Write)?;
 
-        let set = CardSet::from_code(code.set());
-        let set: u32 = set.try_into().map_err(|_| DeckEncodingError::UnknownSet)?;
+        let (set, region) = {
+            let registry = registry();
+            let set = registry.set_by_code(code.set()).ok_or(DeckEncodingError::UnknownSet)?.id;
+            let region = registry.region_by_code(code.region()).ok_or(DeckEncodingError::UnknownRegion)?.id;
+            (set, region)
+        };
+
         writer
             .write_u32_varint(set)
             .map_err(DeckEncodingError::Write)?;
-
-        let region = CardRegion::from_code(code.region());
-        let region: u32 = region
-            .try_into()
-            .map_err(|_| DeckEncodingError::UnknownRegion)?;
         writer
             .write_u32_varint(region)
             .map_err(DeckEncodingError::Write)?;
@@ -363,6 +417,9 @@ impl Deck {
 
     /// Deserialize a deck code into a [`Deck`].
     ///
+    /// Decoding is lenient: data that cannot be read at the end of the deck code is silently ignored, duplicate cards overwrite each other, and cards with zero copies are skipped.
+    /// Use [`Deck::from_code_strict`] to reject such codes instead.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -372,17 +429,88 @@ impl Deck {
     ///     .expect("deck to be deserialized successfully");
     /// ```
     pub fn from_code(code: &str) -> DeckDecodingResult<Deck> {
-        let mut cursor = Cursor::new(Self::decode_code(code)?);
+        Self::decode(code, false)
+    }
 
-        let (format, _version) = Self::read_header(&mut cursor)?;
+    /// Deserialize a deck code into a [`Deck`], rejecting codes with trailing garbage, duplicate cards or zero quantities.
+    ///
+    /// Errors caused by the contents of the code are reported as [`DeckDecodingError::Invalid`], whose [`DeckDecodingDiagnostic`] describes where decoding failed and why.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use patched_porobot::data::deckcode::deck::{Deck, DeckCodeSection, DeckDecodingError, DeckDecodingProblem};
+    ///
+    /// // A valid deck code, followed by a lonely extra byte
+    /// let error = Deck::from_code_strict("CEAAAAIBAEAQQAI").unwrap_err();
+    ///
+    /// match error {
+    ///     DeckDecodingError::Invalid(diagnostic) => {
+    ///         assert_eq!(diagnostic.section, DeckCodeSection::Extras);
+    ///         assert_eq!(diagnostic.problem, DeckDecodingProblem::TrailingData);
+    ///     }
+    ///     _ => panic!("expected a diagnostic"),
+    /// }
+    /// ```
+    pub fn from_code_strict(code: &str) -> DeckDecodingResult<Deck> {
+        Self::decode(code, true)
+    }
 
-        match format {
-            DeckCodeFormat::F1 => Self::read_f1_body(&mut cursor),
-        }
+    /// Deserialize a deck code into a [`DecodedDeck`], keeping the [format](DeckCodeFormat) and [version](DeckCodeVersion) read from its header.
+    ///
+    /// Decoding is lenient, like [`Deck::from_code`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use patched_porobot::data::deckcode::deck::Deck;
+    /// use patched_porobot::data::deckcode::format::DeckCodeFormat;
+    /// use patched_porobot::data::deckcode::version::DeckCodeVersion;
+    ///
+    /// let decoded = Deck::from_code_versioned("CQBQCBAJBUCAKCRYHKTADNIBAYBQSDQ2DQ3FEWACAECQVNQBAIBQSOK5AEAQGCIV")
+    ///     .expect("deck to be deserialized successfully");
+    ///
+    /// assert_eq!(decoded.format, DeckCodeFormat::F1);
+    /// assert_eq!(decoded.version, DeckCodeVersion::V4);
+    /// ```
+    pub fn from_code_versioned(code: &str) -> DeckDecodingResult<DecodedDeck> {
+        Self::decode_versioned(code, false)
+    }
+
+    /// Deserialize a deck code into a [`DecodedDeck`], keeping the [format](DeckCodeFormat) and [version](DeckCodeVersion) read from its header.
+    ///
+    /// Decoding is strict, like [`Deck::from_code_strict`].
+    pub fn from_code_strict_versioned(code: &str) -> DeckDecodingResult<DecodedDeck> {
+        Self::decode_versioned(code, true)
+    }
+
+    /// Deserialize a deck code into a [`Deck`], in either lenient or strict mode.
+    fn decode(code: &str, strict: bool) -> DeckDecodingResult<Deck> {
+        Self::decode_versioned(code, strict).map(|decoded| decoded.deck)
+    }
+
+    /// Deserialize a deck code into a [`DecodedDeck`], in either lenient or strict mode.
+    fn decode_versioned(code: &str, strict: bool) -> DeckDecodingResult<DecodedDeck> {
+        let bytes = Self::decode_code(code)?;
+        let mut reader = DeckCodeReader::new(&bytes, strict);
+
+        let (format, version) = Self::read_header(&mut reader)?;
+
+        let deck = match format {
+            DeckCodeFormat::F1 => Self::read_f1_body(&mut reader)?,
+        };
+
+        Ok(DecodedDeck { deck, format, version })
     }
 
     /// Serialize the [`Deck`] into a deck code of the given [format](DeckCodeFormat).
     ///
+    /// The resulting code is byte-identical to the one produced by [Riot's reference encoder](https://github.com/RiotGames/LoRDeckCodes) for the same deck, so that a deck always has exactly one canonical code:
+    ///
+    /// - the version is the lowest one supporting all the cards in the deck, or [`DeckCodeVersion::V1`] for an empty deck;
+    /// - groups are sorted by ascending number of cards, then by the code of their first card;
+    /// - cards inside groups and extra cards are sorted by card code.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -397,12 +525,58 @@ impl Deck {
     /// d.to_code(DeckCodeFormat::F1).expect("deck to be serialized successfully");
     /// ```
     pub fn to_code(&self, format: DeckCodeFormat) -> DeckEncodingResult<String> {
-        let mut cursor = Cursor::new(Vec::new());
-
         let version = self
             .min_deckcode_version()
             .ok_or(DeckEncodingError::UnknownVersion)?;
 
+        self.to_code_versioned(format, version)
+    }
+
+    /// Serialize the [`Deck`] into a deck code of the given [format](DeckCodeFormat) and [version](DeckCodeVersion).
+    ///
+    /// Useful to target tools which do not support the latest versions; the version must be high enough to represent every card in the deck.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DeckEncodingError::VersionTooLow`] listing the cards requiring a higher version, if there are any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use patched_porobot::deck;
+    /// use patched_porobot::data::deckcode::deck::{Deck, DeckEncodingError};
+    /// use patched_porobot::data::deckcode::format::DeckCodeFormat;
+    /// use patched_porobot::data::deckcode::version::DeckCodeVersion;
+    ///
+    /// let d: Deck = deck!["01DE002": 3, "04SH047": 3];
+    ///
+    /// let code = d.to_code_versioned(DeckCodeFormat::F1, DeckCodeVersion::V5).unwrap();
+    /// assert_eq!(Deck::from_code_versioned(&code).unwrap().version, DeckCodeVersion::V5);
+    ///
+    /// let error = d.to_code_versioned(DeckCodeFormat::F1, DeckCodeVersion::V2).unwrap_err();
+    /// assert!(matches!(error, DeckEncodingError::VersionTooLow { required: DeckCodeVersion::V3, .. }));
+    /// ```
+    pub fn to_code_versioned(&self, format: DeckCodeFormat, version: DeckCodeVersion) -> DeckEncodingResult<String> {
+        let mut too_recent = vec![];
+        let mut required = version;
+
+        for code in self.contents.keys().sorted() {
+            let card_version = code
+                .min_deckcode_version()
+                .ok_or(DeckEncodingError::UnknownVersion)?;
+
+            if card_version > version {
+                too_recent.push(code.clone());
+                required = required.max(card_version);
+            }
+        }
+
+        if !too_recent.is_empty() {
+            return Err(DeckEncodingError::VersionTooLow { version, required, cards: too_recent });
+        }
+
+        let mut cursor = Cursor::new(Vec::new());
+
         Self::write_header(&mut cursor, format, version)?;
 
         match format {
@@ -498,6 +672,142 @@ impl Deck {
             .sum()
     }
 
+    /// Compute the [`DeckStats`] of the deck, such as its mana curve and the number of cards of each type.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use patched_porobot::deck;
+    /// use patched_porobot::data::deckcode::deck::Deck;
+    /// use patched_porobot::data::setbundle::card::CardIndex;
+    /// use patched_porobot::data::setbundle::create_cardindex_from_wd;
+    ///
+    /// let index: CardIndex = create_cardindex_from_wd();
+    /// let deck: Deck = deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA");
+    /// let stats = deck.stats(&index);
+    /// assert_eq!(stats.mana_curve.iter().sum::<u32>(), 40);
+    /// ```
+    pub fn stats(&self, cards: &CardIndex) -> DeckStats {
+        DeckStats::of(self, cards)
+    }
+
+    /// Parse a human-readable decklist, resolving card names with the given [`CardIndex`].
+    ///
+    /// See the [`decklist`](crate::data::deckcode::decklist) module for the supported syntax.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use patched_porobot::deck;
+    /// use patched_porobot::data::deckcode::deck::Deck;
+    /// use patched_porobot::data::setbundle::card::CardIndex;
+    /// use patched_porobot::data::setbundle::create_cardindex_from_wd;
+    ///
+    /// let index: CardIndex = create_cardindex_from_wd();
+    /// let deck = Deck::from_decklist("# Champions\n3x Braum", &index).unwrap();
+    /// assert_eq!(deck, deck!["01FR009": 3]);
+    /// ```
+    pub fn from_decklist(text: &str, cards: &CardIndex) -> DecklistResult<Self> {
+        parse_decklist(text, cards)
+    }
+
+    /// Render the deck as a human-readable decklist, which can be parsed back with [`Deck::from_decklist`].
+    pub fn to_decklist(&self, cards: &CardIndex) -> String {
+        emit_decklist(self, cards)
+    }
+
+    /// Generate the conventional name of the deck, such as `Ezreal Draven (PZ/NX)`.
+    ///
+    /// See [`archetype_name`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use patched_porobot::deck;
+    /// use patched_porobot::data::corebundle::create_globalindexes_from_wd;
+    /// use patched_porobot::data::deckcode::deck::Deck;
+    /// use patched_porobot::data::setbundle::create_cardindex_from_wd;
+    ///
+    /// let index = create_cardindex_from_wd();
+    /// let globals = create_globalindexes_from_wd();
+    /// let deck: Deck = deck!["01FR009": 3, "01FR003": 3];
+    /// assert_eq!(deck.archetype_name(&index, &globals.regions), Some("Braum (FR)".to_string()));
+    /// ```
+    pub fn archetype_name(&self, cards: &CardIndex, regions: &LocalizedCardRegionIndex) -> Option<String> {
+        archetype_name(self, cards, regions)
+    }
+
+    /// Compute the [`DeckDiff`] turning this deck into the given one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use patched_porobot::deck;
+    /// use patched_porobot::data::deckcode::deck::Deck;
+    ///
+    /// let before: Deck = deck!["01DE002": 3, "01DE012": 1];
+    /// let after: Deck = deck!["01DE002": 1, "01DE012": 1, "01DE003": 2];
+    ///
+    /// let diff = before.diff(&after);
+    /// assert_eq!(before.patch(&diff).unwrap(), after);
+    /// ```
+    pub fn diff(&self, other: &Deck) -> DeckDiff {
+        DeckDiff::between(self, other)
+    }
+
+    /// Apply the given [`DeckDiff`] to a copy of the deck.
+    pub fn patch(&self, diff: &DeckDiff) -> Result<Deck, DeckPatchError> {
+        diff.apply(self)
+    }
+
+    /// Compute the [`CraftingCost`] of every card of the deck.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use patched_porobot::deck;
+    /// use patched_porobot::data::deckcode::deck::Deck;
+    /// use patched_porobot::data::setbundle::card::CardIndex;
+    /// use patched_porobot::data::setbundle::create_cardindex_from_wd;
+    /// use patched_porobot::data::setbundle::rarity::CardRarity;
+    ///
+    /// let index: CardIndex = create_cardindex_from_wd();
+    /// let deck: Deck = deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA");
+    /// let cost = deck.crafting_cost(&index);
+    /// assert_eq!(cost.wildcards_of(CardRarity::Champion), 6);
+    /// ```
+    pub fn crafting_cost(&self, cards: &CardIndex) -> CraftingCost {
+        CraftingCost::of(self, cards)
+    }
+
+    /// Compute the [`CraftingCost`] of the cards of the deck missing from the given [`Collection`].
+    pub fn crafting_cost_with(&self, cards: &CardIndex, owned: &Collection) -> CraftingCost {
+        CraftingCost::of_missing(self, cards, owned)
+    }
+
+    /// Compute the probability of the given [`DrawQuery`] being satisfied while playing the deck with the default [`DrawRules`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::collections::HashSet;
+    /// use patched_porobot::deck;
+    /// use patched_porobot::data::deckcode::deck::Deck;
+    /// use patched_porobot::data::deckcode::probability::DrawQuery;
+    /// use patched_porobot::data::setbundle::code::CardCode;
+    ///
+    /// let d: Deck = deck![
+    ///     "01DE002": 1,
+    ///     "01DE012": 39,
+    /// ];
+    ///
+    /// let query = DrawQuery::any_of(HashSet::from([CardCode::from("01DE002".to_string())]), 1);
+    /// assert!((d.draw_probability(&query) - 5.0 / 40.0).abs() < 1e-9);
+    /// ```
+    pub fn draw_probability(&self, query: &DrawQuery) -> f64 {
+        query.probability(self, &DrawRules::default())
+    }
+
     /// Check if the cards contained in the deck are allowed in the given format.
     ///
     /// For compatibility reasons, assumes that cards missing from the [`CardIndex`] are always allowed.
@@ -526,141 +836,405 @@ impl Deck {
 
     /// Find the first possible set of regions that the [`Deck`] fits in.
     ///
-    /// Lower amounts of regions are preferred: the limit will be increased from 1 up to `limit` until a valid solution is found.
+    /// Lower amounts of regions are preferred: the smallest set of at most `limit` regions is returned.
     ///
-    /// # Warning
+    /// Use [`Deck::region_assignment`] to get every possible set of regions instead.
+    pub fn regions(&self, card_index: &CardIndex, limit: usize) -> Option<HashSet<CardRegion>> {
+        self.region_assignment(card_index, limit)
+            .solutions
+            .into_iter()
+            .next()
+    }
+
+    /// Find every minimal set of at most `limit` regions the [`Deck`] fits in, and which cards force which region.
     ///
-    /// This method traverses the tree of possible region selections until one is found.
+    /// Cards missing from the [`CardIndex`] are ignored.
     ///
-    /// The time required to run this function may grow exponentially with the amount of cards in the deck!
-    pub fn regions(&self, card_index: &CardIndex, limit: usize) -> Option<HashSet<CardRegion>> {
-        let cards: Vec<&Card> = self.contents.keys()
-            .flat_map(|cc| cc.to_card(card_index))
-            .collect();
+    /// # Example
+    ///
+    /// ```rust
+    /// use patched_porobot::deck;
+    /// use patched_porobot::data::deckcode::deck::Deck;
+    /// use patched_porobot::data::setbundle::card::CardIndex;
+    /// use patched_porobot::data::setbundle::create_cardindex_from_wd;
+    /// use patched_porobot::data::setbundle::region::CardRegion;
+    ///
+    /// let index: CardIndex = create_cardindex_from_wd();
+    /// let deck: Deck = deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA");
+    /// let assignment = deck.region_assignment(&index, 2);
+    ///
+    /// assert!(!assignment.is_ambiguous());
+    /// assert!(assignment.required_regions().contains(&CardRegion::Ionia));
+    /// ```
+    pub fn region_assignment(&self, card_index: &CardIndex, limit: usize) -> RegionAssignment {
+        let cards = self.contents.keys()
+            .flat_map(|cc| cc.to_card(card_index));
+
+        RegionAssignment::solve(cards, limit)
+    }
 
-        for n in 1..=limit {
-            let result = Self::regions_recursive_first_limit(cards.as_slice(), HashSet::new(), n);
+    /// List the [`LegalityViolation`]s of a [`Deck`] which does not fit in `limit` regions.
+    ///
+    /// An Origin champion is reported as [`LegalityViolation::OriginRequirementUnmet`] only if the deck would fit in the limit without the cards bringing its Origin; otherwise, only [`LegalityViolation::TooManyRegions`] is reported.
+    pub(crate) fn regions_violations(&self, cards: &CardIndex, limit: usize) -> Vec<LegalityViolation> {
+        let mut violations = vec![LegalityViolation::TooManyRegions { limit }];
+
+        let deck_cards = self.contents.keys()
+            .sorted()
+            .filter_map(|cc| cc.to_card(cards))
+            .collect_vec();
 
-            if result.is_some() {
-                return result;
+        for champion in deck_cards.iter() {
+            if let Some(origin) = origin_of(champion) {
+                let others = deck_cards
+                    .iter()
+                    .copied()
+                    .filter(|card| origin_of(card) != Some(origin));
+
+                if RegionAssignment::solve(others, limit).is_solvable() {
+                    violations.push(LegalityViolation::OriginRequirementUnmet { champion: champion.code.clone(), origin });
+                }
             }
         }
 
-        None
-    }
-
-    /// Find the first possible set of regions that covers all given [`Card`]s.
-    ///
-    /// This function is *recursive*: the `cards` parameter holds the [`Card`]s left to process, while the `regions` parameter holds the regions found so far, and the `limit` parameter holds the size of the `regions` set to stop the recursion at.
-    ///
-    /// # Warning
-    ///
-    /// This method traverses the tree of possible region selections until one is found.
-    ///
-    /// The time required to run this function may grow exponentially with the size of `cards`!
-    fn regions_recursive_first_limit(cards: &[&Card], regions: HashSet<CardRegion>, limit: usize) -> Option<HashSet<CardRegion>> {
-        match cards.get(0) {
-            None => Some(regions),
-            Some(card) => {
-                card.regions.iter()
-                    .map(|region| {
-                        match region {
-                            CardRegion::Unsupported => {
-                                None
-                            }
-                            CardRegion::Runeterra => {
-                                Self::regions_recursive_first_limit(&cards[1..], regions.clone(), limit)
-                            }
-                            _ => {
-                                let mut regions = regions.clone();
-                                let inserted = regions.insert(*region);
-                                match inserted && regions.len() > limit {
-                                    true => None,
-                                    false => Self::regions_recursive_first_limit(&cards[1..], regions, limit)
-                                }
-                            }
-                        }
-                    })
-                    .find(Option::is_some)
-                    .unwrap_or(None)
-            },
+        violations
+    }
+
+    /// Check the [`Deck`] against the given [`FormatRules`], listing every rule it does not respect.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use patched_porobot::deck;
+    /// use patched_porobot::data::deckcode::deck::Deck;
+    /// use patched_porobot::data::deckcode::rules::FormatRules;
+    /// use patched_porobot::data::setbundle::card::CardIndex;
+    /// use patched_porobot::data::setbundle::create_cardindex_from_wd;
+    ///
+    /// let index: CardIndex = create_cardindex_from_wd();
+    /// let deck: Deck = deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA");
+    /// assert!(deck.check(&index, &FormatRules::eternal()).is_legal());
+    /// assert!(!deck.check(&index, &FormatRules::singleton()).is_legal());
+    /// ```
+    pub fn check(&self, cards: &CardIndex, rules: &FormatRules) -> LegalityReport {
+        let mut violations = vec![];
+
+        let card_count = self.card_count();
+        if card_count != rules.deck_size {
+            violations.push(LegalityViolation::CardCount { expected: rules.deck_size, found: card_count });
+        }
+
+        for (code, copies) in self.contents.iter().sorted() {
+            if *copies > rules.copies_limit {
+                violations.push(LegalityViolation::TooManyCopies { card: code.clone(), copies: *copies, limit: rules.copies_limit });
+            }
+        }
+
+        if let Some(limit) = rules.champions_limit {
+            let found = self.champions_count(cards);
+            if found > limit {
+                violations.push(LegalityViolation::TooManyChampions { found, limit });
+            }
+        }
+
+        for code in self.contents.keys().sorted() {
+            violations.extend(rules.card_violations(code, cards));
+        }
+
+        let regions = self.regions(cards, rules.regions_limit);
+        if regions.is_none() {
+            violations.extend(self.regions_violations(cards, rules.regions_limit));
+        }
+
+        let regions = match violations.is_empty() {
+            true => regions,
+            false => None,
+        };
+
+        LegalityReport { violations, regions }
+    }
+
+    /// Check if the [`Deck`] is legal for play in the *Eternal* format, listing every rule it does not respect.
+    pub fn check_eternal(&self, cards: &CardIndex) -> LegalityReport {
+        self.check(cards, &FormatRules::eternal())
+    }
+
+    /// Check if the [`Deck`] is legal for play in the *Standard* format, listing every rule it does not respect.
+    pub fn check_standard(&self, cards: &CardIndex) -> LegalityReport {
+        self.check(cards, &FormatRules::standard())
+    }
+
+    /// Check if the [`Deck`] is legal for play in the *Singleton* format, listing every rule it does not respect.
+    pub fn check_singleton(&self, cards: &CardIndex) -> LegalityReport {
+        self.check(cards, &FormatRules::singleton())
+    }
+
+    /// Check if the [`Deck`] is legal for play in the *Unlimited Champions* format, listing every rule it does not respect.
+    pub fn check_unlimited_champions(&self, cards: &CardIndex) -> LegalityReport {
+        self.check(cards, &FormatRules::unlimited_champions())
+    }
+
+    /// Check the [`Deck`] against the given [`FormatRules`] like [`Deck::check`], but consider cards missing from the [`CardIndex`] to always be allowed.
+    ///
+    /// Returns the regions chosen for the deck, or [`None`] if the deck is not legal.
+    fn lenient_regions(&self, cards: &CardIndex, rules: &FormatRules) -> Option<HashSet<CardRegion>> {
+        let report = self.check(cards, rules);
+
+        match report.violations.iter().all(|v| matches!(v, LegalityViolation::UnknownCard(_))) {
+            true => self.regions(cards, rules.regions_limit),
+            false => None,
         }
     }
 
     /// Check if the [`Deck`] is legal for play in the *Eternal* format.
     ///
+    /// Use [`Deck::check_eternal`] to know why a deck is not legal.
+    /// Cards missing from the [`CardIndex`] are always allowed.
+    ///
     /// # Returns
     ///
     /// - `None` if the deck is not legal for *Eternal* play.
     /// - `Some(regions)` if the deck is legal for *Eternal* play considering the specified region set.
     pub fn eternal(&self, cards: &CardIndex) -> Option<HashSet<CardRegion>> {
-        let copies_limit = self.contents.values().all(|n| n <= &3);
-        let cards_limit = self.card_count() == 40;
-        let champions_limit = self.champions_count(cards) <= 6;
-        let are_cards_allowed = self.cards_are_allowed_in(cards, CardFormat::Eternal);
-        let regions = self.regions(cards, 2);
-
-        match copies_limit && cards_limit && champions_limit && are_cards_allowed {
-            false => None,
-            true => regions,
-        }
+        self.lenient_regions(cards, &FormatRules::eternal())
     }
 
     /// Check if the [`Deck`] is legal for play in the *Standard* format.
     ///
+    /// Use [`Deck::check_standard`] to know why a deck is not legal.
+    /// Cards missing from the [`CardIndex`] are always allowed.
+    ///
     /// # Returns
     ///
-    /// - `None` if the deck is not legal for *Eternal* play.
-    /// - `Some(regions)` if the deck is legal for *Eternal* play considering the specified region set.
+    /// - `None` if the deck is not legal for *Standard* play.
+    /// - `Some(regions)` if the deck is legal for *Standard* play considering the specified region set.
     pub fn standard(&self, cards: &CardIndex) -> Option<HashSet<CardRegion>> {
-        let copies_limit = self.contents.values().all(|n| n <= &3);
-        let cards_limit = self.card_count() == 40;
-        let champions_limit = self.champions_count(cards) <= 6;
-        let are_cards_allowed = self.cards_are_allowed_in(cards, CardFormat::Standard);
-        let regions = self.regions(cards, 2);
-
-        match copies_limit && cards_limit && champions_limit && are_cards_allowed {
-            false => None,
-            true => regions,
-        }
+        self.lenient_regions(cards, &FormatRules::standard())
     }
 
     /// Check if the [`Deck`] is legal for play in the *Singleton* format.
     ///
+    /// Use [`Deck::check_singleton`] to know why a deck is not legal.
+    /// Cards missing from the [`CardIndex`] are always allowed.
+    ///
     /// # Returns
     ///
     /// - `None` if the deck is not legal for *Singleton* play.
     /// - `Some(regions)` if the deck is legal for *Singleton* play considering the specified region set.
     pub fn singleton(&self, cards: &CardIndex) -> Option<HashSet<CardRegion>> {
-        let copies_limit = self.contents.values().all(|n| n <= &1);
-        let cards_limit = self.card_count() == 40;
-        let champions_limit = self.champions_count(cards) <= 6;
-        let are_cards_allowed = self.cards_are_allowed_in(cards, CardFormat::Eternal);  // I think?
-        let regions = self.regions(cards, 3);
-
-        match copies_limit && cards_limit && champions_limit && are_cards_allowed {
-            false => None,
-            true => regions,
-        }
+        self.lenient_regions(cards, &FormatRules::singleton())
     }
 
     /// Check if the [`Deck`] is legal to play in the *Unlimited Champions* format.
     ///
+    /// Use [`Deck::check_unlimited_champions`] to know why a deck is not legal.
+    /// Cards missing from the [`CardIndex`] are always allowed.
+    ///
     /// # Returns
     ///
     /// - `None` if the deck is not legal for *Unlimited Champions* play.
     /// - `Some(regions)` if the deck is legal for *Unlimited Champions* play considering the specified region set.
     pub fn unlimited_champions(&self, cards: &CardIndex) -> Option<HashSet<CardRegion>> {
-        let copies_limit = self.contents.values().all(|n| n <= &3);
-        let cards_limit = self.card_count() == 40;
-        let are_cards_allowed = self.cards_are_allowed_in(cards, CardFormat::Eternal);  // I think?
-        let regions = self.regions(cards, 2);
+        self.lenient_regions(cards, &FormatRules::unlimited_champions())
+    }
+}
 
-        match copies_limit && cards_limit && are_cards_allowed {
-            false => None,
-            true => regions,
+/// A cursor over the bytes of a deck code, keeping track of the [`DeckCodeSection`] being read.
+struct DeckCodeReader<'b> {
+    /// The bytes of the deck code.
+    bytes: &'b [u8],
+
+    /// The offset of the next byte to be read.
+    offset: usize,
+
+    /// The section of the deck code currently being read.
+    section: DeckCodeSection,
+
+    /// Whether the deck code is being decoded in strict mode.
+    strict: bool,
+}
+
+impl<'b> DeckCodeReader<'b> {
+    /// The maximum number of bytes a [`u32`] varint can span.
+    const MAX_VARINT_LEN: usize = 5;
+
+    /// Create a new [`DeckCodeReader`] positioned at the header of the given bytes.
+    fn new(bytes: &'b [u8], strict: bool) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+            section: DeckCodeSection::Header,
+            strict,
         }
     }
+
+    /// Whether all bytes of the deck code have been read.
+    fn is_exhausted(&self) -> bool {
+        self.offset >= self.bytes.len()
+    }
+
+    /// Create a [`DeckDecodingError::Invalid`] for the current section at the given offset.
+    fn error_at(&self, offset: usize, expected: DeckCodeExpectation, problem: DeckDecodingProblem) -> DeckDecodingError {
+        DeckDecodingError::Invalid(DeckDecodingDiagnostic {
+            offset,
+            section: self.section,
+            expected,
+            problem,
+        })
+    }
+
+    /// Read a single byte.
+    fn read_byte(&mut self, expected: DeckCodeExpectation) -> DeckDecodingResult<u8> {
+        let byte = *self.bytes
+            .get(self.offset)
+            .ok_or_else(|| self.error_at(self.offset, expected, DeckDecodingProblem::UnexpectedEnd))?;
+
+        self.offset += 1;
+        Ok(byte)
+    }
+
+    /// Read a single unsigned varint.
+    ///
+    /// Varints longer than [`Self::MAX_VARINT_LEN`] bytes or overflowing a [`u32`] are rejected.
+    fn read_varint(&mut self, expected: DeckCodeExpectation) -> DeckDecodingResult<u32> {
+        let start = self.offset;
+        let mut value: u64 = 0;
+
+        for index in 0..Self::MAX_VARINT_LEN {
+            let byte = self.read_byte(expected)?;
+            value |= u64::from(byte & 0b0111_1111) << (7 * index);
+
+            if byte & 0b1000_0000 == 0 {
+                return u32::try_from(value)
+                    .map_err(|_| self.error_at(start, expected, DeckDecodingProblem::InvalidVarint));
+            }
+        }
+
+        Err(self.error_at(start, expected, DeckDecodingProblem::InvalidVarint))
+    }
+}
+
+/// A section of a deck code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DeckCodeSection {
+    /// The header byte, containing the [format](DeckCodeFormat) and [version](DeckCodeVersion) of the code.
+    Header,
+    /// The supergroup of the cards present in the deck with the given number of copies, either 3, 2 or 1.
+    Supergroup(u32),
+    /// The cards present in the deck with a non-standard number of copies.
+    Extras,
+}
+
+impl Display for DeckCodeSection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeckCodeSection::Header => write!(f, "header"),
+            DeckCodeSection::Supergroup(quantity) => write!(f, "{}× cards", quantity),
+            DeckCodeSection::Extras => write!(f, "extra cards"),
+        }
+    }
+}
+
+/// An element of a deck code the decoder was trying to read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DeckCodeExpectation {
+    /// The header byte.
+    FormatAndVersion,
+    /// The number of groups in a supergroup.
+    GroupCount,
+    /// The number of cards in a group.
+    CardCount,
+    /// The numeric id of a set.
+    SetId,
+    /// The numeric id of a region.
+    RegionId,
+    /// The number of a card inside its set and region.
+    CardNumber,
+    /// The number of copies of an extra card.
+    Quantity,
+}
+
+impl Display for DeckCodeExpectation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeckCodeExpectation::FormatAndVersion => write!(f, "format and version"),
+            DeckCodeExpectation::GroupCount => write!(f, "number of groups"),
+            DeckCodeExpectation::CardCount => write!(f, "number of cards"),
+            DeckCodeExpectation::SetId => write!(f, "set id"),
+            DeckCodeExpectation::RegionId => write!(f, "region id"),
+            DeckCodeExpectation::CardNumber => write!(f, "card number"),
+            DeckCodeExpectation::Quantity => write!(f, "number of copies"),
+        }
+    }
+}
+
+/// The reason why a deck code could not be decoded.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DeckDecodingProblem {
+    /// The deck code ended before the expected element could be read.
+    UnexpectedEnd,
+    /// The expected element was not a valid [`u32`] varint.
+    InvalidVarint,
+    /// The deck code format was unknown.
+    UnknownFormat(u8),
+    /// The deck code version was unknown.
+    UnknownVersion(u8),
+    /// The set id is not known to the [`CardIdRegistry`](crate::data::deckcode::registry::CardIdRegistry).
+    UnknownSet(u32),
+    /// The region id is not known to the [`CardIdRegistry`](crate::data::deckcode::registry::CardIdRegistry).
+    UnknownRegion(u32),
+    /// The card was already present in the deck code. Only detected in strict mode.
+    DuplicateCard(CardCode),
+    /// An extra card had zero copies. Only detected in strict mode.
+    ZeroQuantity,
+    /// The deck code ended in the middle of an extra card. Only detected in strict mode.
+    TrailingData,
+}
+
+impl Display for DeckDecodingProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeckDecodingProblem::UnexpectedEnd => write!(f, "the code ended too early"),
+            DeckDecodingProblem::InvalidVarint => write!(f, "the number is malformed"),
+            DeckDecodingProblem::UnknownFormat(format) => write!(f, "format {} is unknown", format),
+            DeckDecodingProblem::UnknownVersion(version) => write!(f, "version {} is unknown", version),
+            DeckDecodingProblem::UnknownSet(set) => write!(f, "set {} is unknown", set),
+            DeckDecodingProblem::UnknownRegion(region) => write!(f, "region {} is unknown", region),
+            DeckDecodingProblem::DuplicateCard(code) => write!(f, "card {} appears more than once", code.full),
+            DeckDecodingProblem::ZeroQuantity => write!(f, "a card has zero copies"),
+            DeckDecodingProblem::TrailingData => write!(f, "the code has unexpected data at its end"),
+        }
+    }
+}
+
+/// Where and why a deck code could not be decoded.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DeckDecodingDiagnostic {
+    /// The offset of the byte where the problematic element starts, counted on the base-32 decoded bytes.
+    pub offset: usize,
+    /// The section of the deck code containing the problematic element.
+    pub section: DeckCodeSection,
+    /// The element the decoder was trying to read.
+    pub expected: DeckCodeExpectation,
+    /// What went wrong.
+    pub problem: DeckDecodingProblem,
+}
+
+impl Display for DeckDecodingDiagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at byte {} ({}), while reading the {}: {}", self.offset, self.section, self.expected, self.problem)
+    }
+}
+
+/// A [`Deck`] decoded from a code, together with the header of the code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedDeck {
+    /// The decoded deck.
+    pub deck: Deck,
+    /// The format the code was encoded in.
+    pub format: DeckCodeFormat,
+    /// The version the code was encoded in, which may be higher than the one required by the deck.
+    pub version: DeckCodeVersion,
 }
 
 /// An error occoured while decoding a [`Deck`] from a code.
@@ -668,16 +1242,17 @@ impl Deck {
 pub enum DeckDecodingError {
     /// The provided string was not a valid base-32 string.
     Base32Encoding(data_encoding::DecodeError),
-    /// The decoder cursor was not able to read data from the byte buffer.
-    Read(std::io::Error),
-    /// The deck code format of the provided string was unknown.
-    UnknownFormat,
-    /// The deck code version of the provided string was unknown.
-    UnknownVersion,
-    /// The deck code contains a set with an unknown short code.
-    UnknownSet,
-    /// The deck code contains a region with an unknown short code.
-    UnknownRegion,
+    /// The decoded bytes did not form a valid deck code.
+    Invalid(DeckDecodingDiagnostic),
+}
+
+impl Display for DeckDecodingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeckDecodingError::Base32Encoding(_) => write!(f, "the code contains characters that are not allowed in deck codes"),
+            DeckDecodingError::Invalid(diagnostic) => write!(f, "{}", diagnostic),
+        }
+    }
 }
 
 /// An error occoured while encoding a [`Deck`] into a code.
@@ -687,12 +1262,37 @@ pub enum DeckEncodingError {
     Write(std::io::Error),
     /// The deck code version of the deck could not be determined.
     UnknownVersion,
-    /// A card in the deck belongs to a set with an unknown internal id.
+    /// A card in the deck belongs to a set with a short code unknown to the [`CardIdRegistry`](crate::data::deckcode::registry::CardIdRegistry).
     UnknownSet,
-    /// A card in the deck belongs to a region with an unknown internal id.
+    /// A card in the deck belongs to a region with a short code unknown to the [`CardIdRegistry`](crate::data::deckcode::registry::CardIdRegistry).
     UnknownRegion,
     /// A card in the deck has a invalid card number segment in the card code.
     InvalidCardNumber(std::num::ParseIntError),
+    /// A card in the deck has zero copies, which cannot be represented in a deck code.
+    ZeroQuantity(CardCode),
+    /// The requested deck code version cannot represent some cards in the deck.
+    VersionTooLow {
+        /// The requested version.
+        version: DeckCodeVersion,
+        /// The lowest version able to represent every card in the deck.
+        required: DeckCodeVersion,
+        /// The cards which cannot be represented in the requested version, sorted by card code.
+        cards: Vec<CardCode>,
+    },
+}
+
+impl Display for DeckEncodingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeckEncodingError::Write(e) => write!(f, "the code could not be written: {}", e),
+            DeckEncodingError::UnknownVersion => write!(f, "the version of the code could not be determined"),
+            DeckEncodingError::UnknownSet => write!(f, "a card belongs to an unknown set"),
+            DeckEncodingError::UnknownRegion => write!(f, "a card belongs to an unknown region"),
+            DeckEncodingError::InvalidCardNumber(e) => write!(f, "a card has an invalid number: {}", e),
+            DeckEncodingError::ZeroQuantity(code) => write!(f, "card {} has zero copies", code.full),
+            DeckEncodingError::VersionTooLow { version, required, cards } => write!(f, "cards {} require version {:?} instead of {:?}", cards.iter().map(|code| &code.full).join(", "), required, version),
+        }
+    }
 }
 
 /// The [`Result`] of a [`Deck`] **decoding** operation, for example [`Deck::from_code`].
@@ -789,6 +1389,7 @@ mod tests {
     }
 
     // riot's code is perfect and their examples always work correctly
+    // (this one predates the group sorting of their encoder, so it cannot be reproduced byte-by-byte)
     test_de_ser!(test_de_ser_riotexample,   "CEAAECABAQJRWHBIFU2DOOYIAEBAMCIMCINCILJZAICACBANE4VCYBABAILR2HRL", ignore);
 
     test_de_ser!(test_de_ser_yordlestar,    "CQBQCBAJBUCAKCRYHKTADNIBAYBQSDQ2DQ3FEWACAECQVNQBAIBQSOK5AEAQGCIV");
@@ -806,6 +1407,49 @@ mod tests {
     test_de_ser!(test_de_ser_paltriunholy,  "CQCQCAYBAIAQIAIPAECQVIABAEDAOCIFAEAQGCAJCA2QIAICAEBQCBABBIAQMBYOAMCAOO2MNUAQCBQHBM");
     // test_de_ser!(test_de_ser_, "");
 
+    macro_rules! test_strict {
+        ( $id:ident, $src:literal, $offset:expr, $section:expr, $problem:expr ) => {
+            #[test]
+            fn $id() {
+                match Deck::from_code_strict($src) {
+                    Err(DeckDecodingError::Invalid(diagnostic)) => {
+                        assert_eq!(diagnostic.offset, $offset);
+                        assert_eq!(diagnostic.section, $section);
+                        assert_eq!(diagnostic.problem, $problem);
+                    }
+                    other => panic!("expected a diagnostic, got {:?}", other),
+                }
+            }
+        };
+    }
+
+    test_strict!(test_strict_truncated,    "CEAAAAIBAEAQ",        7, DeckCodeSection::Supergroup(1), DeckDecodingProblem::UnexpectedEnd);
+    test_strict!(test_strict_trailing,     "CEAAAAIBAEAQQAI",     8, DeckCodeSection::Extras,        DeckDecodingProblem::TrailingData);
+    test_strict!(test_strict_duplicate,    "CEAAAAICAEAQQCA",     8, DeckCodeSection::Supergroup(1), DeckDecodingProblem::DuplicateCard(CardCode::from_s_r_c("01", "FR", 8)));
+    test_strict!(test_strict_duplicateext, "CEAAAAABAEAQQAIBAEEA", 11, DeckCodeSection::Extras,      DeckDecodingProblem::DuplicateCard(CardCode::from_s_r_c("01", "FR", 8)));
+    test_strict!(test_strict_zeroquantity, "CEAAAAAAAEAQQ",       4, DeckCodeSection::Extras,        DeckDecodingProblem::ZeroQuantity);
+    test_strict!(test_strict_unknownregion, "CEAAAAABAFRQQ",      6, DeckCodeSection::Extras,        DeckDecodingProblem::UnknownRegion(99));
+
+    #[test]
+    fn test_strict_valid() {
+        let strict = Deck::from_code_strict("CQBQCBAJBUCAKCRYHKTADNIBAYBQSDQ2DQ3FEWACAECQVNQBAIBQSOK5AEAQGCIV").expect("deck to deserialize successfully");
+        let lenient = Deck::from_code("CQBQCBAJBUCAKCRYHKTADNIBAYBQSDQ2DQ3FEWACAECQVNQBAIBQSOK5AEAQGCIV").expect("deck to deserialize successfully");
+        assert_eq!(strict, lenient);
+    }
+
+    #[test]
+    fn test_lenient_trailing() {
+        let deck = Deck::from_code("CEAAAAIBAEAQQAI").expect("deck to deserialize successfully");
+        assert_eq!(deck, deck!["01FR008": 1]);
+    }
+
+    #[test]
+    fn test_lenient_zeroquantity() {
+        let deck = Deck::from_code("CEAAAAAAAEAQQ").expect("deck to deserialize successfully");
+        assert_eq!(deck, deck![]);
+        assert!(deck.to_code(DeckCodeFormat::F1).is_ok());
+    }
+
     macro_rules! test_ser_de {
         ( $id:ident, $deck:expr ) => {
             #[test]
@@ -926,9 +1570,58 @@ mod tests {
     test_ser_de!(test_ser_de_morepowder, deck![
         "02BW012": 69,
     ]);
+    test_ser_de!(test_ser_de_newersets, deck![
+        "06RU001": 4,
+        "07NX008": 5,
+        "08BC017": 6,
+        "09SH010": 2,
+    ]);
 
     // test_ser_de!(test_ser_de_, deck![]);
 
+    #[test]
+    fn test_reference_vectors() {
+        let vectors = include_str!("DeckCodesTestData.txt");
+
+        let entries = vectors
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect_vec();
+
+        let mut count = 0;
+        for entry in entries.split(|line| line.is_empty()).filter(|entry| !entry.is_empty()) {
+            let (code, cards) = entry.split_first().unwrap();
+
+            let deck = Deck {
+                contents: cards
+                    .iter()
+                    .map(|line| {
+                        let (quantity, card) = line.split_once(':').expect("line to be in the quantity:card format");
+                        (CardCode::from(card.to_string()), quantity.parse().expect("quantity to be a number"))
+                    })
+                    .collect(),
+            };
+
+            assert_eq!(Deck::from_code_strict(code).expect("deck to deserialize successfully"), deck, "decoding {}", code);
+            assert_eq!(&deck.to_code(DeckCodeFormat::F1).expect("deck to serialize successfully"), code, "encoding {}", code);
+            count += 1;
+        }
+
+        assert_eq!(count, 34);
+    }
+
+    #[test]
+    fn test_ser_empty() {
+        let code = deck![].to_code(DeckCodeFormat::F1).expect("deck to serialize successfully");
+        assert_eq!(code, "CEAAAAA");
+    }
+
+    #[test]
+    fn test_ser_zeroquantity() {
+        let error = deck!["01DE002": 0].to_code(DeckCodeFormat::F1).unwrap_err();
+        assert!(matches!(error, DeckEncodingError::ZeroQuantity(_)));
+    }
+
     macro_rules! test_legality {
         ( $id:ident, $deck:expr, $check:path, $assert:expr ) => {
             #[test]
@@ -1055,4 +1748,103 @@ mod tests {
         deck!("CQAAADABAICACAIFBLAACAIFAEHQCBQBEQBAGBADAQBAIAIKBUBAKBAWDUBQIBACA4GAMAIBAMCAYHJBGADAMBAOCQKRMKBLA4AQIAQ3D4QSIKZYBACAODJ3JRIW3AABQIAYUAI"),
         Deck::unlimited_champions, false
     );
+
+    #[test]
+    fn test_legality_unknown_card_allowed() {
+        let mut index = crate::data::setbundle::create_cardindex_from_wd();
+        let deck = deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA");
+        let missing = deck.contents.keys().find(|cc| cc.to_card(&index).unwrap().supertype != CardSupertype::Champion).unwrap().clone();
+        index.remove(&missing);
+
+        assert!(!deck.check_eternal(&index).is_legal());
+        assert!(deck.eternal(&index).is_some());
+    }
+
+    #[test]
+    fn test_report_legal() {
+        let index = crate::data::setbundle::create_cardindex_from_wd();
+        let deck = deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA");
+        let report = deck.check_eternal(&index);
+        assert!(report.is_legal());
+        assert_eq!(report.regions, deck.eternal(&index));
+        assert!(report.regions.is_some());
+    }
+
+    #[test]
+    fn test_report_violations() {
+        use crate::data::deckcode::legality::LegalityViolation;
+
+        let index = crate::data::setbundle::create_cardindex_from_wd();
+        let deck = deck![
+            "01DE002": 4,
+            "99XX999": 1,
+        ];
+        let report = deck.check_eternal(&index);
+        assert!(!report.is_legal());
+        assert_eq!(report.regions, None);
+        assert!(report.violations.contains(&LegalityViolation::CardCount { expected: 40, found: 5 }));
+        assert!(report.violations.contains(&LegalityViolation::TooManyCopies { card: CardCode::from("01DE002".to_string()), copies: 4, limit: 3 }));
+        assert!(report.violations.contains(&LegalityViolation::UnknownCard(CardCode::from("99XX999".to_string()))));
+    }
+
+    #[test]
+    fn test_report_rules() {
+        use crate::data::deckcode::legality::LegalityViolation;
+        use crate::data::deckcode::rules::FormatRules;
+
+        let index = crate::data::setbundle::create_cardindex_from_wd();
+        let deck = deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA");
+
+        let report = deck.check(&index, &FormatRules::even_cost_cards());
+        assert!(report.violations.iter().all(|v| matches!(v, LegalityViolation::CostNotAllowed { .. })));
+        assert!(!report.violations.is_empty());
+
+        let report = deck.check(&index, &FormatRules::commons_only());
+        assert!(report.violations.iter().any(|v| matches!(v, LegalityViolation::RarityNotAllowed { .. })));
+    }
+
+    #[test]
+    fn test_report_origins() {
+        use crate::data::deckcode::legality::LegalityViolation;
+        use crate::data::deckcode::rules::FormatRules;
+
+        let index = crate::data::setbundle::create_cardindex_from_wd();
+        let is_origin_violation = |v: &LegalityViolation| matches!(v, LegalityViolation::OriginRequirementUnmet { .. });
+
+        let report = deck!["06RU001": 1, "01DE002": 1, "01FR003": 1, "01IO003": 1].check(&index, &FormatRules::eternal());
+        assert!(report.violations.contains(&LegalityViolation::TooManyRegions { limit: 2 }));
+        assert!(!report.violations.iter().any(is_origin_violation));
+
+        let report = deck!["06RU001": 1, "01DE002": 1, "01FR003": 1].check(&index, &FormatRules::eternal());
+        assert!(report.violations.contains(&LegalityViolation::TooManyRegions { limit: 2 }));
+        assert!(report.violations.contains(&LegalityViolation::OriginRequirementUnmet { champion: CardCode::from("06RU001".to_string()), origin: CardRegion::Bard }));
+
+        let poro_king = LegalityViolation::OriginRequirementUnmet { champion: CardCode::from("07RU015".to_string()), origin: CardRegion::PoroKing };
+        assert_eq!(poro_king.to_string(), "contains 07RU015, but its other cards do not fit in the Poro King origin and the other regions");
+    }
+
+    #[test]
+    fn test_versioned_roundtrip() {
+        let deck = deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA");
+        let minimum = deck.min_deckcode_version().unwrap();
+        assert_eq!(Deck::from_code_versioned(&deck.to_code(DeckCodeFormat::F1).unwrap()).unwrap().version, minimum);
+
+        let code = deck.to_code_versioned(DeckCodeFormat::F1, DeckCodeVersion::V5).unwrap();
+        let decoded = Deck::from_code_strict_versioned(&code).unwrap();
+        assert_eq!(decoded, DecodedDeck { deck, format: DeckCodeFormat::F1, version: DeckCodeVersion::V5 });
+    }
+
+    #[test]
+    fn test_versioned_too_low() {
+        let deck = deck!["01DE002": 3, "04SH047": 3, "06BC011": 1];
+
+        match deck.to_code_versioned(DeckCodeFormat::F1, DeckCodeVersion::V2) {
+            Err(DeckEncodingError::VersionTooLow { version, required, cards }) => {
+                assert_eq!(version, DeckCodeVersion::V2);
+                assert_eq!(required, DeckCodeVersion::V4);
+                assert_eq!(cards, vec![CardCode::from("04SH047".to_string()), CardCode::from("06BC011".to_string())]);
+            }
+            other => panic!("expected VersionTooLow, got {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,383 @@
+//! Module defining parsers and emitters for human-readable decklists, such as:
+//!
+//! ```text
+//! # Champions
+//! 3x Braum
+//!
+//! # Spells
+//! 3x Poro Cannon
+//! ```
+//!
+//! Each entry is made of a quantity and a card name, in either the `3x Poro Cannon` or the `Poro Cannon x3` form.
+//!
+//! Lines starting with `#` or `//`, and lines ending with `:` are treated as section headers, and ignored when parsing.
+//!
+//! Card names are matched against the names of the collectible cards of a [`CardIndex`], so they have to be written in the locale the index was loaded in.
+//!
+//! Card codes are accepted as well, either instead of the name or after it in parenthesis, like `3x Poro Cannon (01FR004)`; the emitter uses the latter form for cards whose name is shared with other cards.
+
+use crate::data::deckcode::deck::Deck;
+use crate::data::setbundle::card::{Card, CardIndex};
+use crate::data::setbundle::code::CardCode;
+use crate::data::setbundle::r#type::CardType;
+use crate::data::setbundle::supertype::CardSupertype;
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+lazy_static! {
+    /// Regex matching entries in the `3x Poro Cannon` form.
+    static ref QUANTITY_FIRST_RE: Regex = Regex::new(r"^(?P<qty>\d+)\s*[xX×]?\s+(?P<name>.+)$").unwrap();
+
+    /// Regex matching entries in the `Poro Cannon x3` form.
+    static ref QUANTITY_LAST_RE: Regex = Regex::new(r"^(?P<name>.+?)\s+[xX×]\s*(?P<qty>\d+)$").unwrap();
+
+    /// Regex matching card codes, optionally preceded by a card name and enclosed in parenthesis.
+    static ref CARD_CODE_RE: Regex = Regex::new(r"(?:^|\()(?P<code>\d{2}[A-Za-z]{2}\d{3}(?:T\d+)?)\)?$").unwrap();
+}
+
+/// The reason why a line of a decklist could not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DecklistProblem {
+    /// The line is neither an entry nor a section header.
+    Malformed,
+    /// The quantity of the entry is zero, or too large.
+    InvalidQuantity,
+    /// No card matches the name of the entry.
+    UnknownCard(String),
+    /// More than one card matches the name of the entry.
+    AmbiguousCard(String, Vec<CardCode>),
+}
+
+impl Display for DecklistProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecklistProblem::Malformed => write!(f, "the line is not a card entry"),
+            DecklistProblem::InvalidQuantity => write!(f, "the quantity is not valid"),
+            DecklistProblem::UnknownCard(name) => write!(f, "no card is named {:?}", name),
+            DecklistProblem::AmbiguousCard(name, codes) => write!(f, "{:?} could be any of {}", name, codes.iter().map(|c| &c.full).join(", ")),
+        }
+    }
+}
+
+/// Where and why a line of a decklist could not be parsed.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DecklistDiagnostic {
+    /// The number of the line, starting from 1.
+    pub line: usize,
+    /// What went wrong.
+    pub problem: DecklistProblem,
+}
+
+impl Display for DecklistDiagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at line {}: {}", self.line, self.problem)
+    }
+}
+
+/// An error occoured while parsing a [`Deck`] from a decklist.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecklistError {
+    /// The decklist contains no entries.
+    Empty,
+    /// Some lines of the decklist could not be parsed.
+    Invalid(Vec<DecklistDiagnostic>),
+}
+
+impl Display for DecklistError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecklistError::Empty => write!(f, "the decklist contains no cards"),
+            DecklistError::Invalid(diagnostics) => write!(f, "{}", diagnostics.iter().join("; ")),
+        }
+    }
+}
+
+/// The [`Result`] of parsing a decklist.
+pub type DecklistResult<T> = Result<T, DecklistError>;
+
+/// Resolver of card names to [`CardCode`]s, tolerating differences in capitalization, punctuation and small typos.
+pub struct CardNameMatcher<'c> {
+    /// The codes of the collectible cards, indexed by their [normalized](normalize) name.
+    names: HashMap<String, Vec<&'c CardCode>>,
+}
+
+impl<'c> CardNameMatcher<'c> {
+    /// Create a new [`CardNameMatcher`] for the collectible cards of the given [`CardIndex`].
+    pub fn new(cards: &'c CardIndex) -> Self {
+        let mut names: HashMap<String, Vec<&'c CardCode>> = HashMap::new();
+
+        for card in cards.values().filter(|card| card.collectible) {
+            names.entry(normalize(&card.name)).or_default().push(&card.code);
+        }
+        for codes in names.values_mut() {
+            codes.sort();
+        }
+
+        Self { names }
+    }
+
+    /// Find the [`CardCode`] of the card with the given name.
+    ///
+    /// Names are matched exactly first, then by edit distance, and finally as a part of a longer name; in every step, matching more than one card is an error.
+    pub fn resolve(&self, name: &str) -> Result<CardCode, DecklistProblem> {
+        if let Some(captures) = CARD_CODE_RE.captures(name) {
+            return Ok(CardCode::from(captures["code"].to_ascii_uppercase()));
+        }
+
+        let normalized = normalize(name);
+        if normalized.is_empty() {
+            return Err(DecklistProblem::UnknownCard(name.to_string()));
+        }
+
+        if let Some(codes) = self.names.get(&normalized) {
+            return self.pick(name, codes.iter().copied());
+        }
+
+        let threshold = (normalized.chars().count() / 4).max(1);
+        let closest = self.names
+            .iter()
+            .map(|(candidate, codes)| (edit_distance(&normalized, candidate), codes))
+            .filter(|(distance, _)| *distance <= threshold)
+            .min_set_by_key(|(distance, _)| *distance);
+        if !closest.is_empty() {
+            return self.pick(name, closest.into_iter().flat_map(|(_, codes)| codes.iter().copied()));
+        }
+
+        let containing = self.names
+            .iter()
+            .filter(|(candidate, _)| candidate.contains(&normalized))
+            .flat_map(|(_, codes)| codes.iter().copied())
+            .collect_vec();
+        self.pick(name, containing.into_iter())
+    }
+
+    /// Return the only [`CardCode`] of the given ones, or an error if there are none or more than one.
+    fn pick<'a>(&self, name: &str, codes: impl Iterator<Item = &'a CardCode>) -> Result<CardCode, DecklistProblem> {
+        let codes = codes.sorted().dedup().cloned().collect_vec();
+
+        match codes.len() {
+            0 => Err(DecklistProblem::UnknownCard(name.to_string())),
+            1 => Ok(codes.into_iter().next().expect("codes to have an element")),
+            _ => Err(DecklistProblem::AmbiguousCard(name.to_string(), codes)),
+        }
+    }
+}
+
+/// Parse a decklist into a [`Deck`], resolving card names with the given [`CardIndex`].
+///
+/// Entries referring to the same card are merged.
+pub fn parse_decklist(text: &str, cards: &CardIndex) -> DecklistResult<Deck> {
+    let matcher = CardNameMatcher::new(cards);
+    let mut contents: HashMap<CardCode, u32> = HashMap::new();
+    let mut diagnostics: Vec<DecklistDiagnostic> = vec![];
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("//") || line.ends_with(':') {
+            continue;
+        }
+
+        let problem = match parse_entry(line, &matcher) {
+            Ok((code, quantity)) => {
+                *contents.entry(code).or_default() += quantity;
+                continue;
+            }
+            Err(problem) => problem,
+        };
+
+        diagnostics.push(DecklistDiagnostic {
+            line: index + 1,
+            problem,
+        });
+    }
+
+    if !diagnostics.is_empty() {
+        return Err(DecklistError::Invalid(diagnostics));
+    }
+    if contents.is_empty() {
+        return Err(DecklistError::Empty);
+    }
+
+    Ok(Deck { contents })
+}
+
+/// Parse a single entry of a decklist.
+fn parse_entry(line: &str, matcher: &CardNameMatcher) -> Result<(CardCode, u32), DecklistProblem> {
+    let captures = QUANTITY_FIRST_RE
+        .captures(line)
+        .or_else(|| QUANTITY_LAST_RE.captures(line))
+        .ok_or(DecklistProblem::Malformed)?;
+
+    let quantity: u32 = captures["qty"]
+        .parse()
+        .ok()
+        .filter(|quantity| *quantity > 0)
+        .ok_or(DecklistProblem::InvalidQuantity)?;
+
+    let code = matcher.resolve(captures["name"].trim())?;
+
+    Ok((code, quantity))
+}
+
+/// A predicate checking whether a [`Card`] belongs to a section of an emitted decklist.
+type SectionPredicate = fn(&Card) -> bool;
+
+/// Render a [`Deck`] as a decklist, using the names of the cards in the given [`CardIndex`].
+///
+/// Cards are split in sections by their type, and sorted by cost and name; cards missing from the index are listed by code in a last section.
+pub fn emit_decklist(deck: &Deck, cards: &CardIndex) -> String {
+    let sections: [(&str, SectionPredicate); 5] = [
+        ("Champions", |card| card.supertype == CardSupertype::Champion),
+        ("Units", |card| card.supertype != CardSupertype::Champion && card.r#type == CardType::Unit),
+        ("Spells", |card| card.supertype != CardSupertype::Champion && card.r#type == CardType::Spell),
+        ("Landmarks", |card| card.supertype != CardSupertype::Champion && card.r#type == CardType::Landmark),
+        ("Equipments", |card| card.supertype != CardSupertype::Champion && card.r#type == CardType::Equipment),
+    ];
+
+    let matcher = CardNameMatcher::new(cards);
+    let entry = |card: &Card, quantity: u32| match matcher.resolve(&card.name) {
+        Ok(code) if code == card.code => format!("{}x {}", quantity, card.name),
+        _ => format!("{}x {} ({})", quantity, card.name, card.code.full),
+    };
+
+    let known = deck.contents
+        .iter()
+        .filter_map(|(code, quantity)| code.to_card(cards).map(|card| (card, *quantity)))
+        .sorted_by(|(a, _), (b, _)| a.cost.cmp(&b.cost).then(a.name.cmp(&b.name)).then(a.code.cmp(&b.code)))
+        .collect_vec();
+
+    let mut blocks = sections
+        .iter()
+        .map(|(title, predicate)| {
+            let entries = known
+                .iter()
+                .filter(|(card, _)| predicate(card))
+                .map(|(card, quantity)| entry(card, *quantity))
+                .collect_vec();
+            (title.to_string(), entries)
+        })
+        .collect_vec();
+
+    let others = known
+        .iter()
+        .filter(|(card, _)| sections.iter().all(|(_, predicate)| !predicate(card)))
+        .map(|(card, quantity)| entry(card, *quantity))
+        .chain(deck.contents
+            .iter()
+            .filter(|(code, _)| code.to_card(cards).is_none())
+            .sorted()
+            .map(|(code, quantity)| format!("{}x {}", quantity, code.full)))
+        .collect_vec();
+    blocks.push(("Others".to_string(), others));
+
+    blocks
+        .into_iter()
+        .filter(|(_, entries)| !entries.is_empty())
+        .map(|(title, entries)| format!("# {}\n{}", title, entries.join("\n")))
+        .join("\n\n")
+}
+
+/// Normalize a card name for matching, keeping only its lowercase alphanumeric characters.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// Compute the Levenshtein distance between two strings, counted in [`char`]s.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect_vec();
+    let mut previous = (0..=b.len()).collect_vec();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::setbundle::create_cardindex_from_wd;
+    use crate::deck;
+
+    fn code(code: &str) -> CardCode {
+        CardCode::from(code.to_string())
+    }
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("braum", "braum"), 0);
+        assert_eq!(edit_distance("brum", "braum"), 1);
+        assert_eq!(edit_distance("", "poro"), 4);
+    }
+
+    #[test]
+    fn resolve_names() {
+        let index = create_cardindex_from_wd();
+        let matcher = CardNameMatcher::new(&index);
+
+        assert_eq!(matcher.resolve("Braum"), Ok(code("01FR009")));
+        assert_eq!(matcher.resolve("  bRaUm "), Ok(code("01FR009")));
+        assert_eq!(matcher.resolve("Brum"), Ok(code("01FR009")));
+        assert_eq!(matcher.resolve("01fr009"), Ok(code("01FR009")));
+        assert_eq!(matcher.resolve("Anything (01FR009)"), Ok(code("01FR009")));
+        assert!(matches!(matcher.resolve("Zzzzzzzzzzzz"), Err(DecklistProblem::UnknownCard(_))));
+        assert!(matches!(matcher.resolve("Poro"), Err(DecklistProblem::AmbiguousCard(_, _))));
+    }
+
+    #[test]
+    fn parse_with_headers() {
+        let index = create_cardindex_from_wd();
+        let deck = parse_decklist("# Champions\n3x Braum\n\nSpells:\nBraum x2\n", &index).unwrap();
+
+        assert_eq!(deck, deck!["01FR009": 5]);
+    }
+
+    #[test]
+    fn parse_reports_every_problem() {
+        let index = create_cardindex_from_wd();
+        let error = parse_decklist("3x Braum\nBraum\n0x Braum\n3x Zzzzzzzzzzzz", &index).unwrap_err();
+
+        match error {
+            DecklistError::Invalid(diagnostics) => {
+                assert_eq!(diagnostics.iter().map(|d| d.line).collect_vec(), vec![2, 3, 4]);
+                assert_eq!(diagnostics[0].problem, DecklistProblem::Malformed);
+                assert_eq!(diagnostics[1].problem, DecklistProblem::InvalidQuantity);
+            }
+            _ => panic!("expected invalid decklist"),
+        }
+
+        assert_eq!(parse_decklist("# Nothing here", &index), Err(DecklistError::Empty));
+    }
+
+    #[test]
+    fn round_trip() {
+        let index = create_cardindex_from_wd();
+        let deck: Deck = deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA");
+
+        let text = emit_decklist(&deck, &index);
+        assert!(text.starts_with("# Champions\n"));
+        assert_eq!(parse_decklist(&text, &index).unwrap(), deck);
+    }
+
+    #[test]
+    fn round_trip_unknown() {
+        let index = create_cardindex_from_wd();
+        let deck: Deck = deck!["99XX999": 2, "01FR009": 1];
+
+        let text = emit_decklist(&deck, &index);
+        assert_eq!(text, "# Champions\n1x Braum\n\n# Others\n2x 99XX999");
+        assert_eq!(parse_decklist(&text, &index).unwrap(), deck);
+    }
+}
@@ -0,0 +1,213 @@
+//! Module defining the [`DeckDiff`] between two [`Deck`]s, which can be displayed or applied as a patch to other decks.
+
+use crate::data::deckcode::deck::Deck;
+use crate::data::setbundle::card::CardIndex;
+use crate::data::setbundle::code::CardCode;
+use itertools::Itertools;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+/// A change to the number of copies of a card.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DeckChange {
+    /// The changed card.
+    pub card: CardCode,
+    /// The number of copies before the change.
+    pub before: u32,
+    /// The number of copies after the change.
+    pub after: u32,
+}
+
+impl DeckChange {
+    /// The number of copies added by the change, negative if copies were removed.
+    pub fn delta(&self) -> i64 {
+        i64::from(self.after) - i64::from(self.before)
+    }
+
+    /// Whether the card was not in the deck before the change.
+    pub fn is_addition(&self) -> bool {
+        self.before == 0
+    }
+
+    /// Whether the card is not in the deck after the change.
+    pub fn is_removal(&self) -> bool {
+        self.after == 0
+    }
+}
+
+/// The differences between two [`Deck`]s.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeckDiff {
+    /// The cards whose number of copies changed, sorted by card code.
+    pub changes: Vec<DeckChange>,
+}
+
+impl DeckDiff {
+    /// Compare two [`Deck`]s, finding the changes required to turn `before` into `after`.
+    pub fn between(before: &Deck, after: &Deck) -> Self {
+        let codes: HashSet<&CardCode> = before.contents.keys().chain(after.contents.keys()).collect();
+
+        let changes = codes
+            .into_iter()
+            .sorted()
+            .map(|card| DeckChange {
+                card: card.clone(),
+                before: before.copies_of(card),
+                after: after.copies_of(card),
+            })
+            .filter(|change| change.before != change.after)
+            .collect();
+
+        Self { changes }
+    }
+
+    /// Whether the two [`Deck`]s are equal.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The cards which were not in the first deck.
+    pub fn added(&self) -> impl Iterator<Item = &DeckChange> {
+        self.changes.iter().filter(|change| change.is_addition())
+    }
+
+    /// The cards which are not in the second deck.
+    pub fn removed(&self) -> impl Iterator<Item = &DeckChange> {
+        self.changes.iter().filter(|change| change.is_removal())
+    }
+
+    /// The cards which are in both decks, with a different number of copies.
+    pub fn changed(&self) -> impl Iterator<Item = &DeckChange> {
+        self.changes.iter().filter(|change| !change.is_addition() && !change.is_removal())
+    }
+
+    /// Get the [`DeckDiff`] undoing this one.
+    pub fn inverted(&self) -> Self {
+        let changes = self.changes
+            .iter()
+            .map(|change| DeckChange {
+                card: change.card.clone(),
+                before: change.after,
+                after: change.before,
+            })
+            .collect();
+
+        Self { changes }
+    }
+
+    /// Apply the number of copies added and removed by this diff to the given [`Deck`].
+    ///
+    /// The patched deck does not need to be the one the diff was computed from, but it must have enough copies of the removed cards.
+    pub fn apply(&self, deck: &Deck) -> Result<Deck, DeckPatchError> {
+        let mut contents = deck.contents.clone();
+
+        for change in self.changes.iter() {
+            let copies = deck.copies_of(&change.card);
+            let patched = i64::from(copies) + change.delta();
+
+            match u32::try_from(patched) {
+                Ok(0) => {
+                    contents.remove(&change.card);
+                }
+                Ok(patched) => {
+                    contents.insert(change.card.clone(), patched);
+                }
+                Err(_) => return Err(DeckPatchError::NotEnoughCopies {
+                    card: change.card.clone(),
+                    copies,
+                    removed: change.before - change.after,
+                }),
+            }
+        }
+
+        Ok(Deck { contents })
+    }
+
+    /// Render the diff as a human-readable list of changes, such as `−2 Poro Cannon, +1 Ledros`, using the names of the cards in the given [`CardIndex`].
+    ///
+    /// Removals are listed first; cards missing from the index are displayed by code.
+    pub fn display(&self, cards: &CardIndex) -> String {
+        self.changes
+            .iter()
+            .sorted_by_key(|change| change.delta() > 0)
+            .map(|change| {
+                let name = change.card
+                    .to_card(cards)
+                    .map_or_else(|| change.card.full.clone(), |card| card.name.clone());
+
+                match change.delta() > 0 {
+                    true => format!("+{} {}", change.delta(), name),
+                    false => format!("−{} {}", -change.delta(), name),
+                }
+            })
+            .join(", ")
+    }
+}
+
+/// An error occoured while applying a [`DeckDiff`] to a [`Deck`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeckPatchError {
+    /// The deck has less copies of a card than the diff removes.
+    NotEnoughCopies {
+        /// The card with not enough copies.
+        card: CardCode,
+        /// The number of copies in the deck.
+        copies: u32,
+        /// The number of copies removed by the diff.
+        removed: u32,
+    },
+}
+
+impl Display for DeckPatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeckPatchError::NotEnoughCopies { card, copies, removed } => write!(f, "the deck has {} copies of {}, but {} should be removed", copies, card.full, removed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::setbundle::create_cardindex_from_wd;
+    use crate::deck;
+
+    #[test]
+    fn diff_between() {
+        let before: Deck = deck!["01DE001": 3, "01DE002": 2, "01DE003": 1];
+        let after: Deck = deck!["01DE001": 3, "01DE002": 3, "01DE004": 2];
+        let diff = DeckDiff::between(&before, &after);
+
+        assert_eq!(diff.changes.len(), 3);
+        assert_eq!(diff.added().map(|c| c.card.full.as_str()).collect_vec(), vec!["01DE004"]);
+        assert_eq!(diff.removed().map(|c| c.card.full.as_str()).collect_vec(), vec!["01DE003"]);
+        assert_eq!(diff.changed().map(|c| c.delta()).collect_vec(), vec![1]);
+        assert!(DeckDiff::between(&before, &before).is_empty());
+    }
+
+    #[test]
+    fn apply_patch() {
+        let before: Deck = deck!["01DE001": 3, "01DE002": 2, "01DE003": 1];
+        let after: Deck = deck!["01DE001": 3, "01DE002": 3, "01DE004": 2];
+        let diff = DeckDiff::between(&before, &after);
+
+        assert_eq!(diff.apply(&before), Ok(after.clone()));
+        assert_eq!(diff.inverted().apply(&after), Ok(before));
+
+        let variant: Deck = deck!["01DE002": 1, "01DE003": 3];
+        assert_eq!(diff.apply(&variant), Ok(deck!["01DE002": 2, "01DE003": 2, "01DE004": 2]));
+
+        let missing: Deck = deck!["01DE002": 1];
+        assert!(matches!(diff.apply(&missing), Err(DeckPatchError::NotEnoughCopies { copies: 0, removed: 1, .. })));
+    }
+
+    #[test]
+    fn display_diff() {
+        let index = create_cardindex_from_wd();
+        let before: Deck = deck!["01FR009": 2, "99XX999": 1];
+        let after: Deck = deck!["01FR009": 3];
+        let diff = DeckDiff::between(&before, &after);
+
+        assert_eq!(diff.display(&index), "−1 99XX999, +1 Braum");
+    }
+}
@@ -0,0 +1,90 @@
+//! Module defining [`extract_decks`], which finds every deck code contained in arbitrary text.
+
+use crate::data::deckcode::deck::Deck;
+use crate::data::setbundle::card::CardIndex;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::ops::Range;
+
+lazy_static! {
+    /// Regex matching words which could be deck codes.
+    static ref CANDIDATE_RE: Regex = Regex::new(r"\b[A-Z2-7]{8,}\b").unwrap();
+}
+
+/// A deck code found in a text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FoundDeck {
+    /// The byte range of the deck code in the text.
+    pub span: Range<usize>,
+    /// The deck code, as it appears in the text.
+    pub code: String,
+    /// The decoded deck.
+    pub deck: Deck,
+}
+
+/// Find every valid deck code in the given text, in order of appearance.
+///
+/// To reject uppercase words which happen to be valid base-32, a code is only considered valid if it decodes to a non-empty [`Deck`] whose cards are all in the given [`CardIndex`].
+///
+/// # Example
+///
+/// ```rust
+/// use patched_porobot::data::deckcode::extract::extract_decks;
+/// use patched_porobot::data::setbundle::create_cardindex_from_wd;
+///
+/// let index = create_cardindex_from_wd();
+/// let text = "GOOD LUCK with CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA at the TOURNAMENT!";
+///
+/// let found = extract_decks(text, &index);
+/// assert_eq!(found.len(), 1);
+/// assert_eq!(found[0].span, 15..73);
+/// assert_eq!(found[0].deck.card_count(), 40);
+/// ```
+pub fn extract_decks(text: &str, cards: &CardIndex) -> Vec<FoundDeck> {
+    CANDIDATE_RE
+        .find_iter(text)
+        .filter_map(|candidate| {
+            let deck = Deck::from_code(candidate.as_str()).ok()?;
+
+            let is_plausible = !deck.contents.is_empty()
+                && deck.contents.iter().all(|(code, quantity)| *quantity > 0 && code.to_card(cards).is_some());
+            if !is_plausible {
+                return None;
+            }
+
+            Some(FoundDeck {
+                span: candidate.range(),
+                code: candidate.as_str().to_string(),
+                deck,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::setbundle::create_cardindex_from_wd;
+    use crate::deck;
+
+    #[test]
+    fn extract_many() {
+        let index = create_cardindex_from_wd();
+        let text = "Lineup:\n1. CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA\n2. https://example.com/?deck=CQBQCBAJBUCAKCRYHKTADNIBAYBQSDQ2DQ3FEWACAECQVNQBAIBQSOK5AEAQGCIV.";
+
+        let found = extract_decks(text, &index);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].deck, deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA"));
+        assert_eq!(&text[found[1].span.clone()], found[1].code);
+        assert_eq!(found[1].code, "CQBQCBAJBUCAKCRYHKTADNIBAYBQSDQ2DQ3FEWACAECQVNQBAIBQSOK5AEAQGCIV");
+    }
+
+    #[test]
+    fn reject_words() {
+        let index = create_cardindex_from_wd();
+
+        assert!(extract_decks("ABSOLUTELY NOTHING HERE, JUST SHOUTING", &index).is_empty());
+        assert!(extract_decks("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAAx", &index).is_empty());
+        assert!(extract_decks("", &index).is_empty());
+    }
+}
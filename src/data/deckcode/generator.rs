@@ -0,0 +1,406 @@
+//! Module defining a generator of random [`Deck`]s legal in a format.
+//!
+//! Generation is deterministic: the same [`GeneratorOptions`] and [`CardIndex`] always produce the same [`Deck`].
+
+use crate::data::deckcode::deck::Deck;
+use crate::data::deckcode::legality::LegalityReport;
+use crate::data::deckcode::origins::{fits_origin, origin_of};
+use crate::data::deckcode::rules::FormatRules;
+use crate::data::deckcode::stats::MANA_CURVE_BUCKETS;
+use crate::data::setbundle::card::{Card, CardIndex};
+use crate::data::setbundle::code::CardCode;
+use crate::data::setbundle::region::CardRegion;
+use crate::data::setbundle::supertype::CardSupertype;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The number of decks generated before giving up on finding a legal one.
+const MAX_ATTEMPTS: usize = 16;
+
+/// The options of a random [`Deck`] generation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneratorOptions {
+    /// The rules of the format the generated deck has to be legal in.
+    pub rules: FormatRules,
+
+    /// The champions which have to be in the deck.
+    pub champions: Vec<CardCode>,
+
+    /// The regions of the deck, or [`None`] to choose them randomly.
+    pub regions: Option<HashSet<CardRegion>>,
+
+    /// The relative weight of the cards of each mana cost, with the same buckets of [`DeckStats::mana_curve`](crate::data::deckcode::stats::DeckStats::mana_curve), or [`None`] to weight all costs equally.
+    ///
+    /// Cards in buckets with a weight of zero are never chosen.
+    pub curve: Option<[f64; MANA_CURVE_BUCKETS]>,
+
+    /// The seed of the generation, or [`None`] to use a seed based on the current time.
+    pub seed: Option<u64>,
+}
+
+impl GeneratorOptions {
+    /// Generate random decks legal in the format described by the given [`FormatRules`], without any other constraint.
+    pub fn for_format(rules: FormatRules) -> Self {
+        Self {
+            rules,
+            champions: vec![],
+            regions: None,
+            curve: None,
+            seed: None,
+        }
+    }
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        Self::for_format(FormatRules::eternal())
+    }
+}
+
+/// A [`Deck`] produced by [`generate_deck`], with the seed used to produce it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GeneratedDeck {
+    /// The generated deck.
+    pub deck: Deck,
+    /// The seed which, used again, produces the same deck.
+    pub seed: u64,
+}
+
+/// An error occoured while generating a random [`Deck`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GenerationError {
+    /// A fixed champion is missing from the [`CardIndex`].
+    UnknownChampion(CardCode),
+    /// A fixed card is not a champion.
+    NotAChampion(CardCode),
+    /// More champions were fixed than the format allows.
+    TooManyChampions,
+    /// Not enough cards are allowed by the options to fill a deck.
+    NotEnoughCards,
+    /// No legal deck was found; contains the report of the last attempt.
+    NoLegalDeck(LegalityReport),
+}
+
+impl Display for GenerationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GenerationError::UnknownChampion(code) => write!(f, "champion {} is unknown", code.full),
+            GenerationError::NotAChampion(code) => write!(f, "card {} is not a champion", code.full),
+            GenerationError::TooManyChampions => write!(f, "too many champions were requested"),
+            GenerationError::NotEnoughCards => write!(f, "not enough cards match the requested options"),
+            GenerationError::NoLegalDeck(report) => write!(f, "no legal deck was found, as the last one {}", report.violations.iter().join(", ")),
+        }
+    }
+}
+
+/// A minimal [SplitMix64](https://prng.di.unimi.it/splitmix64.c) pseudo-random number generator, used so that seeds produce the same decks across versions and platforms.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    /// Generate the next random [`u64`].
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Generate a random [`f64`] in `0.0..1.0`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Generate a random [`u32`] in `low..=high`.
+    fn range(&mut self, low: u32, high: u32) -> u32 {
+        low + (self.next_u64() % u64::from(high - low + 1)) as u32
+    }
+
+    /// Choose a random element of the given slice, weighting each of them with the given function.
+    fn choose_weighted<'a, T>(&mut self, items: &'a [T], weight: impl Fn(&T) -> f64) -> Option<&'a T> {
+        let total: f64 = items.iter().map(&weight).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut target = self.next_f64() * total;
+        for item in items.iter() {
+            target -= weight(item);
+            if target < 0.0 {
+                return Some(item);
+            }
+        }
+
+        items.iter().rev().find(|item| weight(item) > 0.0)
+    }
+}
+
+/// Whether the given region can be chosen as one of the regions of a generated deck.
+fn is_playable(region: &CardRegion) -> bool {
+    !matches!(region, CardRegion::Runeterra | CardRegion::Unsupported) && !region.is_origin()
+}
+
+/// Generate a random [`Deck`] legal according to the [`FormatRules`] of the given [`GeneratorOptions`].
+///
+/// Every generated deck is validated with [`Deck::check`]; if it is not legal, a new one is generated, up to a fixed number of attempts.
+///
+/// # Example
+///
+/// ```rust
+/// use patched_porobot::data::deckcode::format::DeckCodeFormat;
+/// use patched_porobot::data::deckcode::generator::{generate_deck, GeneratorOptions};
+/// use patched_porobot::data::deckcode::rules::FormatRules;
+/// use patched_porobot::data::setbundle::create_cardindex_from_wd;
+///
+/// let index = create_cardindex_from_wd();
+/// let options = GeneratorOptions {
+///     seed: Some(42),
+///     ..GeneratorOptions::for_format(FormatRules::eternal())
+/// };
+///
+/// let generated = generate_deck(&index, &options).unwrap();
+/// assert!(generated.deck.check_eternal(&index).is_legal());
+/// assert!(generated.deck.to_code(DeckCodeFormat::F1).is_ok());
+/// ```
+pub fn generate_deck(cards: &CardIndex, options: &GeneratorOptions) -> Result<GeneratedDeck, GenerationError> {
+    let seed = options.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos() as u64)
+    });
+    let mut rng = SplitMix64(seed);
+    let rules = &options.rules;
+
+    let champions = options.champions
+        .iter()
+        .unique()
+        .map(|code| {
+            let card = code.to_card(cards).ok_or_else(|| GenerationError::UnknownChampion(code.clone()))?;
+            match card.supertype {
+                CardSupertype::Champion => Ok(card),
+                _ => Err(GenerationError::NotAChampion(code.clone())),
+            }
+        })
+        .collect::<Result<Vec<&Card>, GenerationError>>()?;
+
+    let fixed_copies = match rules.champions_limit {
+        Some(_) if champions.is_empty() => rules.copies_limit,
+        Some(limit) if champions.len() as u32 > limit => return Err(GenerationError::TooManyChampions),
+        Some(limit) => rules.copies_limit.min(limit / champions.len() as u32),
+        None => rules.copies_limit,
+    };
+
+    let pool = cards
+        .values()
+        .filter(|card| card.collectible)
+        .filter(|card| rules.allows_format_of(card) && rules.allows_rarity_of(card) && rules.allows_cost_of(card))
+        .filter(|card| options.curve.map_or(true, |curve| {
+            let bucket = usize::try_from(card.cost).unwrap_or(usize::MAX).min(MANA_CURVE_BUCKETS - 1);
+            curve[bucket] > 0.0
+        }))
+        .sorted_by(|a, b| a.code.cmp(&b.code))
+        .collect_vec();
+
+    let weight = |card: &&Card| -> f64 {
+        match options.curve {
+            None => 1.0,
+            Some(curve) => curve[usize::try_from(card.cost).unwrap_or(usize::MAX).min(MANA_CURVE_BUCKETS - 1)],
+        }
+    };
+
+    let mut last_report = None;
+
+    for _ in 0..MAX_ATTEMPTS {
+        let regions = match &options.regions {
+            Some(regions) => regions.clone(),
+            None => choose_regions(&mut rng, &champions, &pool, rules.regions_limit),
+        };
+        let origins = regions.iter().filter(|region| region.is_origin()).copied().collect_vec();
+
+        let fits = |card: &Card| -> bool {
+            match origin_of(card) {
+                Some(origin) => regions.contains(&origin),
+                None => card.regions.iter().any(|region| regions.contains(region))
+                    || origins.iter().any(|origin| fits_origin(*origin, card)),
+            }
+        };
+
+        let mut contents: HashMap<CardCode, u32> = HashMap::new();
+        for champion in champions.iter() {
+            contents.insert(champion.code.clone(), fixed_copies);
+        }
+
+        let mut candidates = pool
+            .iter()
+            .copied()
+            .filter(|card| fits(card) && !contents.contains_key(&card.code))
+            .collect_vec();
+
+        loop {
+            let count: u32 = contents.values().sum();
+            let champion_count: u32 = contents
+                .iter()
+                .filter(|(code, _)| code.to_card(cards).is_some_and(|card| card.supertype == CardSupertype::Champion))
+                .map(|(_, quantity)| quantity)
+                .sum();
+
+            if count >= rules.deck_size {
+                break;
+            }
+
+            let champion_room = rules.champions_limit.map_or(u32::MAX, |limit| limit.saturating_sub(champion_count));
+            candidates.retain(|card| card.supertype != CardSupertype::Champion || champion_room > 0);
+
+            let card = match rng.choose_weighted(&candidates, weight) {
+                Some(card) => *card,
+                None => break,
+            };
+
+            let mut quantity = rng.range(1, rules.copies_limit.max(1)).min(rules.deck_size - count);
+            if card.supertype == CardSupertype::Champion {
+                quantity = quantity.min(champion_room);
+            }
+
+            contents.insert(card.code.clone(), quantity);
+            candidates.retain(|c| c.code != card.code);
+        }
+
+        let deck = Deck { contents };
+        if deck.card_count() < rules.deck_size && options.regions.is_some() {
+            return Err(GenerationError::NotEnoughCards);
+        }
+
+        let report = deck.check(cards, rules);
+        if report.is_legal() {
+            return Ok(GeneratedDeck { deck, seed });
+        }
+        last_report = Some(report);
+    }
+
+    match last_report {
+        Some(report) => Err(GenerationError::NoLegalDeck(report)),
+        None => Err(GenerationError::NotEnoughCards),
+    }
+}
+
+/// Choose random regions for a deck, starting with the ones of the fixed champions.
+fn choose_regions(rng: &mut SplitMix64, champions: &[&Card], pool: &[&Card], limit: usize) -> HashSet<CardRegion> {
+    let mut regions: HashSet<CardRegion> = HashSet::new();
+
+    for champion in champions.iter() {
+        if let Some(origin) = origin_of(champion) {
+            regions.insert(origin);
+            continue;
+        }
+        if champion.regions.iter().any(|region| regions.contains(region)) {
+            continue;
+        }
+
+        let playable = champion.regions.iter().copied().filter(is_playable).collect_vec();
+        if let Some(region) = rng.choose_weighted(&playable, |_| 1.0) {
+            regions.insert(*region);
+        }
+    }
+
+    let mut available = pool
+        .iter()
+        .flat_map(|card| card.regions.iter().copied())
+        .filter(|region| is_playable(region) && !regions.contains(region))
+        .unique()
+        .sorted()
+        .collect_vec();
+
+    while regions.len() < limit {
+        let region = match rng.choose_weighted(&available, |_| 1.0) {
+            Some(region) => *region,
+            None => break,
+        };
+        regions.insert(region);
+        available.retain(|r| *r != region);
+    }
+
+    regions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::deckcode::format::DeckCodeFormat;
+    use crate::data::setbundle::create_cardindex_from_wd;
+
+    fn code(code: &str) -> CardCode {
+        CardCode::from(code.to_string())
+    }
+
+    #[test]
+    fn seeded_generation_is_deterministic() {
+        let index = create_cardindex_from_wd();
+        let options = GeneratorOptions { seed: Some(7), ..GeneratorOptions::default() };
+
+        let a = generate_deck(&index, &options).unwrap();
+        let b = generate_deck(&index, &options).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.seed, 7);
+    }
+
+    #[test]
+    fn generated_decks_are_legal() {
+        let index = create_cardindex_from_wd();
+
+        for rules in [FormatRules::standard(), FormatRules::eternal(), FormatRules::singleton(), FormatRules::commons_only(), FormatRules::even_cost_cards()] {
+            for seed in 0..4 {
+                let options = GeneratorOptions { seed: Some(seed), ..GeneratorOptions::for_format(rules.clone()) };
+                let generated = generate_deck(&index, &options).unwrap_or_else(|e| panic!("{} seed {}: {}", rules.name, seed, e));
+
+                assert!(generated.deck.check(&index, &rules).is_legal());
+                assert!(generated.deck.to_code(DeckCodeFormat::F1).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn fixed_champions_and_regions() {
+        let index = create_cardindex_from_wd();
+        let options = GeneratorOptions {
+            champions: vec![code("01FR009")],
+            regions: Some(HashSet::from([CardRegion::Freljord, CardRegion::Demacia])),
+            seed: Some(1),
+            ..GeneratorOptions::default()
+        };
+
+        let generated = generate_deck(&index, &options).unwrap();
+        assert_eq!(generated.deck.copies_of(&code("01FR009")), 3);
+        assert!(generated.deck
+            .contents
+            .keys()
+            .filter_map(|c| c.to_card(&index))
+            .all(|card| card.regions.contains(&CardRegion::Freljord) || card.regions.contains(&CardRegion::Demacia)));
+    }
+
+    #[test]
+    fn curve_shape() {
+        let index = create_cardindex_from_wd();
+        let mut curve = [0.0; MANA_CURVE_BUCKETS];
+        curve[1] = 1.0;
+        curve[2] = 1.0;
+
+        let options = GeneratorOptions { curve: Some(curve), seed: Some(3), ..GeneratorOptions::default() };
+        let generated = generate_deck(&index, &options).unwrap();
+
+        let stats = generated.deck.stats(&index);
+        assert_eq!(stats.mana_curve[1] + stats.mana_curve[2], 40);
+    }
+
+    #[test]
+    fn invalid_champions() {
+        let index = create_cardindex_from_wd();
+
+        let unknown = GeneratorOptions { champions: vec![code("99XX999")], ..GeneratorOptions::default() };
+        assert_eq!(generate_deck(&index, &unknown), Err(GenerationError::UnknownChampion(code("99XX999"))));
+
+        let follower = GeneratorOptions { champions: vec![code("01DE002")], ..GeneratorOptions::default() };
+        assert_eq!(generate_deck(&index, &follower), Err(GenerationError::NotAChampion(code("01DE002"))));
+    }
+}
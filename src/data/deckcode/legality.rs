@@ -0,0 +1,109 @@
+//! Module defining the [`LegalityReport`] of a [`Deck`](crate::data::deckcode::deck::Deck) checked against some [`FormatRules`](crate::data::deckcode::rules::FormatRules), and the [`LegalityViolation`]s it may contain.
+
+use crate::data::setbundle::code::CardCode;
+use crate::data::setbundle::format::CardFormat;
+use crate::data::setbundle::rarity::CardRarity;
+use crate::data::setbundle::region::CardRegion;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+/// The result of checking a [`Deck`](crate::data::deckcode::deck::Deck) against the rules of a format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LegalityReport {
+    /// Every rule of the format the deck does not respect.
+    pub violations: Vec<LegalityViolation>,
+
+    /// The regions chosen for the deck, or [`None`] if the deck is not legal.
+    pub regions: Option<HashSet<CardRegion>>,
+}
+
+impl LegalityReport {
+    /// Whether the deck respects every rule of the format.
+    pub fn is_legal(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Convert the report into the regions chosen for the deck, discarding the violations.
+    pub fn into_regions(self) -> Option<HashSet<CardRegion>> {
+        self.regions
+    }
+}
+
+/// A rule of a format not respected by a [`Deck`](crate::data::deckcode::deck::Deck).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LegalityViolation {
+    /// The deck does not have the required number of cards.
+    CardCount {
+        /// The number of cards required by the format.
+        expected: u32,
+        /// The number of cards in the deck.
+        found: u32,
+    },
+    /// The deck contains too many copies of a card.
+    TooManyCopies {
+        /// The card with too many copies.
+        card: CardCode,
+        /// The number of copies in the deck.
+        copies: u32,
+        /// The maximum number of copies allowed by the format.
+        limit: u32,
+    },
+    /// The deck contains too many champion cards.
+    TooManyChampions {
+        /// The number of champion cards in the deck.
+        found: u32,
+        /// The maximum number of champion cards allowed by the format.
+        limit: u32,
+    },
+    /// The deck contains a card which is not allowed in the format.
+    CardNotAllowed {
+        /// The card not allowed in the format.
+        card: CardCode,
+        /// The [`CardFormat`] the card is missing.
+        format: CardFormat,
+    },
+    /// The deck contains a card of a rarity which is not allowed in the format.
+    RarityNotAllowed {
+        /// The card not allowed in the format.
+        card: CardCode,
+        /// The rarity of the card.
+        rarity: CardRarity,
+    },
+    /// The deck contains a card with a mana cost which is not allowed in the format.
+    CostNotAllowed {
+        /// The card not allowed in the format.
+        card: CardCode,
+        /// The mana cost of the card.
+        cost: u64,
+    },
+    /// The cards of the deck do not fit in the maximum number of regions allowed by the format.
+    TooManyRegions {
+        /// The maximum number of regions allowed by the format.
+        limit: usize,
+    },
+    /// The deck contains a Runeterra champion, but the cards of the deck do not fit in its Origin and the other regions allowed by the format.
+    OriginRequirementUnmet {
+        /// The Runeterra champion.
+        champion: CardCode,
+        /// The Origin brought by the champion.
+        origin: CardRegion,
+    },
+    /// The deck contains a card missing from the [`CardIndex`](crate::data::setbundle::card::CardIndex).
+    UnknownCard(CardCode),
+}
+
+impl Display for LegalityViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LegalityViolation::CardCount { expected, found } => write!(f, "has {} cards instead of {}", found, expected),
+            LegalityViolation::TooManyCopies { card, copies, limit } => write!(f, "has {} copies of {}, but at most {} are allowed", copies, card.full, limit),
+            LegalityViolation::TooManyChampions { found, limit } => write!(f, "has {} champions, but at most {} are allowed", found, limit),
+            LegalityViolation::CardNotAllowed { card, format } => write!(f, "contains {}, which is not allowed in {}", card.full, <&str>::from(format)),
+            LegalityViolation::RarityNotAllowed { card, rarity } => write!(f, "contains {}, whose rarity {} is not allowed", card.full, <&str>::from(rarity)),
+            LegalityViolation::CostNotAllowed { card, cost } => write!(f, "contains {}, whose cost {} is not allowed", card.full, cost),
+            LegalityViolation::TooManyRegions { limit } => write!(f, "does not fit in {} regions", limit),
+            LegalityViolation::OriginRequirementUnmet { champion, origin } => write!(f, "contains {}, but its other cards do not fit in the {} origin and the other regions", champion.full, <&str>::from(origin)),
+            LegalityViolation::UnknownCard(card) => write!(f, "contains {}, which is unknown", card.full),
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! Module defining [`Lineup`]s of multiple [`Deck`]s submitted together to a tournament, and the [`LineupRules`] they can be checked against.
+
+use crate::data::deckcode::deck::Deck;
+use crate::data::deckcode::legality::LegalityViolation;
+use crate::data::deckcode::rules::FormatRules;
+use crate::data::setbundle::card::CardIndex;
+use crate::data::setbundle::code::CardCode;
+use crate::data::setbundle::region::CardRegion;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+/// Multiple [`Deck`]s submitted together, for example to a tournament.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lineup {
+    /// The decks of the lineup, in submission order.
+    pub decks: Vec<Deck>,
+}
+
+impl Lineup {
+    /// Create a new [`Lineup`] from the given [`Deck`]s.
+    pub fn new(decks: Vec<Deck>) -> Self {
+        Self { decks }
+    }
+
+    /// Check the lineup against the given [`LineupRules`], listing every rule it does not respect.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use patched_porobot::deck;
+    /// use patched_porobot::data::deckcode::lineup::{Lineup, LineupRules};
+    /// use patched_porobot::data::setbundle::create_cardindex_from_wd;
+    ///
+    /// let index = create_cardindex_from_wd();
+    /// let lineup = Lineup::new(vec![
+    ///     deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA"),
+    ///     deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA"),
+    /// ]);
+    ///
+    /// let report = lineup.check(&index, &LineupRules::conquest());
+    /// assert!(!report.is_legal());
+    /// ```
+    pub fn check(&self, cards: &CardIndex, rules: &LineupRules) -> LineupReport {
+        let mut violations = vec![];
+
+        if self.decks.len() != rules.decks_count {
+            violations.push(LineupViolation::DeckCount { expected: rules.decks_count, found: self.decks.len() });
+        }
+
+        let reports = self.decks
+            .iter()
+            .map(|deck| deck.check(cards, &rules.deck_rules))
+            .collect_vec();
+
+        for (index, report) in reports.iter().enumerate() {
+            if !report.is_legal() {
+                violations.push(LineupViolation::IllegalDeck { deck: index, violations: report.violations.clone() });
+            }
+        }
+
+        if let Some(limit) = rules.champion_appearances_limit {
+            let mut appearances: HashMap<&CardCode, Vec<usize>> = HashMap::new();
+            for (index, deck) in self.decks.iter().enumerate() {
+                for champion in deck.champions(cards) {
+                    appearances.entry(&champion.code).or_default().push(index);
+                }
+            }
+
+            for (champion, decks) in appearances.into_iter().sorted() {
+                if decks.len() > limit {
+                    violations.push(LineupViolation::SharedChampion { champion: champion.clone(), decks, limit });
+                }
+            }
+        }
+
+        // Decks with ambiguous regions may be counted with any of their minimal region sets
+        let choices = reports
+            .iter()
+            .zip(self.decks.iter())
+            .map(|(report, deck)| match report.is_legal() {
+                true => deck.region_assignment(cards, rules.deck_rules.regions_limit).solutions,
+                false => vec![HashSet::new()],
+            })
+            .collect_vec();
+
+        let overused = |regions: &[&HashSet<CardRegion>]| -> Vec<LineupViolation> {
+            let limit = match rules.region_appearances_limit {
+                Some(limit) => limit,
+                None => return vec![],
+            };
+
+            let mut appearances: HashMap<CardRegion, Vec<usize>> = HashMap::new();
+            for (index, set) in regions.iter().enumerate() {
+                for region in set.iter() {
+                    appearances.entry(*region).or_default().push(index);
+                }
+            }
+
+            appearances
+                .into_iter()
+                .sorted()
+                .filter(|(_, decks)| decks.len() > limit)
+                .map(|(region, decks)| LineupViolation::SharedRegion { region, decks, limit })
+                .collect()
+        };
+
+        let combinations = choices.iter().map(|solutions| solutions.iter()).multi_cartesian_product().collect_vec();
+        let chosen = combinations
+            .iter()
+            .find(|combination| overused(combination).is_empty())
+            .or_else(|| combinations.first());
+
+        let regions = match chosen {
+            Some(combination) => {
+                violations.extend(overused(combination));
+                combination
+                    .iter()
+                    .zip(reports.iter())
+                    .map(|(set, report)| report.is_legal().then(|| (*set).clone()))
+                    .collect()
+            }
+            None => vec![None; self.decks.len()],
+        };
+
+        LineupReport { violations, regions }
+    }
+}
+
+/// The constraints a [`Lineup`] has to respect to be accepted by a tournament.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineupRules {
+    /// The name of the lineup format.
+    pub name: String,
+
+    /// The number of decks the lineup must contain.
+    pub decks_count: usize,
+
+    /// The [`FormatRules`] every deck must be legal according to.
+    pub deck_rules: FormatRules,
+
+    /// The maximum number of decks a single champion may appear in, or [`None`] if champions may be shared freely.
+    pub champion_appearances_limit: Option<usize>,
+
+    /// The maximum number of decks a single region may appear in, or [`None`] if regions may be shared freely.
+    pub region_appearances_limit: Option<usize>,
+}
+
+impl LineupRules {
+    /// The rules of a *Conquest* lineup: three *Standard* decks, without shared champions or regions.
+    pub fn conquest() -> Self {
+        Self {
+            name: "Conquest".to_string(),
+            decks_count: 3,
+            deck_rules: FormatRules::standard(),
+            champion_appearances_limit: Some(1),
+            region_appearances_limit: Some(1),
+        }
+    }
+
+    /// The rules of a *Last Stand* lineup: three *Standard* decks, without shared champions, with each region appearing in at most two decks.
+    pub fn last_stand() -> Self {
+        Self {
+            name: "Last Stand".to_string(),
+            region_appearances_limit: Some(2),
+            ..Self::conquest()
+        }
+    }
+}
+
+/// The result of checking a [`Lineup`] against some [`LineupRules`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineupReport {
+    /// Every rule of the lineup format the lineup does not respect.
+    pub violations: Vec<LineupViolation>,
+
+    /// The regions chosen for each deck of the lineup, or [`None`] for the decks which are not legal.
+    pub regions: Vec<Option<HashSet<CardRegion>>>,
+}
+
+impl LineupReport {
+    /// Whether the lineup respects every rule of the lineup format.
+    pub fn is_legal(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// A rule of a lineup format not respected by a [`Lineup`].
+///
+/// Decks are identified by their index in [`Lineup::decks`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LineupViolation {
+    /// The lineup does not have the required number of decks.
+    DeckCount {
+        /// The number of decks required.
+        expected: usize,
+        /// The number of decks in the lineup.
+        found: usize,
+    },
+    /// A deck of the lineup is not legal in the format of the lineup.
+    IllegalDeck {
+        /// The illegal deck.
+        deck: usize,
+        /// The rules of the format the deck does not respect.
+        violations: Vec<LegalityViolation>,
+    },
+    /// A champion appears in too many decks.
+    SharedChampion {
+        /// The shared champion.
+        champion: CardCode,
+        /// The decks containing the champion.
+        decks: Vec<usize>,
+        /// The maximum number of decks the champion may appear in.
+        limit: usize,
+    },
+    /// A region appears in too many decks.
+    SharedRegion {
+        /// The shared region.
+        region: CardRegion,
+        /// The decks using the region.
+        decks: Vec<usize>,
+        /// The maximum number of decks the region may appear in.
+        limit: usize,
+    },
+}
+
+impl Display for LineupViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let decks = |decks: &Vec<usize>| decks.iter().map(|deck| format!("#{}", deck + 1)).join(", ");
+
+        match self {
+            LineupViolation::DeckCount { expected, found } => write!(f, "has {} decks instead of {}", found, expected),
+            LineupViolation::IllegalDeck { deck, violations } => write!(f, "has deck #{}, which {}", deck + 1, violations.iter().join(", ")),
+            LineupViolation::SharedChampion { champion, decks: d, limit } => write!(f, "has {} in decks {}, but at most {} may contain it", champion.full, decks(d), limit),
+            LineupViolation::SharedRegion { region, decks: d, limit } => write!(f, "has {} in decks {}, but at most {} may use it", <&str>::from(region), decks(d), limit),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::setbundle::create_cardindex_from_wd;
+    use crate::deck;
+
+    /// Build a 40 cards deck out of some champions and followers, all with 3 copies except the last follower.
+    fn build(index: &CardIndex, champions: &[&str], followers: &[&str]) -> Deck {
+        let mut contents: HashMap<CardCode, u32> = HashMap::new();
+        for code in champions.iter().chain(followers.iter()) {
+            contents.insert(CardCode::from(code.to_string()), 3);
+        }
+        let count: u32 = contents.values().sum();
+        let last = CardCode::from(followers.last().unwrap().to_string());
+        *contents.get_mut(&last).unwrap() -= count - 40;
+
+        let deck = Deck { contents };
+        assert_eq!(deck.card_count(), 40);
+        assert!(deck.check_eternal(index).is_legal(), "{:?}", deck.check_eternal(index).violations);
+        deck
+    }
+
+    fn followers(index: &CardIndex, region: CardRegion, count: usize) -> Vec<String> {
+        index
+            .values()
+            .filter(|card| card.collectible && card.regions == vec![region])
+            .filter(|card| card.supertype == crate::data::setbundle::supertype::CardSupertype::None)
+            .map(|card| card.code.full.clone())
+            .sorted()
+            .take(count)
+            .collect()
+    }
+
+    #[test]
+    fn shared_champions_and_regions() {
+        let index = create_cardindex_from_wd();
+        let demacia = followers(&index, CardRegion::Demacia, 13);
+        let freljord = followers(&index, CardRegion::Freljord, 13);
+        let demacia = demacia.iter().map(String::as_str).collect_vec();
+        let freljord = freljord.iter().map(String::as_str).collect_vec();
+
+        let a = build(&index, &["01DE045"], &demacia);
+        let b = build(&index, &["01DE045"], &demacia);
+        let c = build(&index, &["01FR009"], &freljord);
+
+        let rules = LineupRules { deck_rules: FormatRules::eternal(), decks_count: 2, ..LineupRules::conquest() };
+
+        let shared = Lineup::new(vec![a.clone(), b]).check(&index, &rules);
+        assert!(shared.violations.contains(&LineupViolation::SharedChampion { champion: CardCode::from("01DE045".to_string()), decks: vec![0, 1], limit: 1 }));
+        assert!(shared.violations.contains(&LineupViolation::SharedRegion { region: CardRegion::Demacia, decks: vec![0, 1], limit: 1 }));
+
+        let distinct = Lineup::new(vec![a, c]).check(&index, &rules);
+        assert!(distinct.is_legal(), "{:?}", distinct.violations);
+        assert_eq!(distinct.regions[1], Some(HashSet::from([CardRegion::Freljord])));
+    }
+
+    #[test]
+    fn illegal_decks_and_count() {
+        let index = create_cardindex_from_wd();
+        let lineup = Lineup::new(vec![deck!["01DE002": 40]]);
+        let report = lineup.check(&index, &LineupRules::last_stand());
+
+        assert_eq!(report.violations[0], LineupViolation::DeckCount { expected: 3, found: 1 });
+        assert!(matches!(report.violations[1], LineupViolation::IllegalDeck { deck: 0, .. }));
+        assert_eq!(report.regions, vec![None]);
+    }
+}
@@ -2,6 +2,22 @@
 //!
 //! Adapted from [RiotGames/LoRDeckCodes](https://github.com/RiotGames/LoRDeckCodes) and from [iulianR/lordeckcodes-rs](https://github.com/iulianR/lordeckcodes-rs).
 
+pub mod archetype;
+pub mod builder;
+pub mod crafting;
 pub mod deck;
+pub mod decklist;
+pub mod diff;
+pub mod extract;
 pub mod format;
+pub mod generator;
+pub mod legality;
+pub mod lineup;
+pub mod origins;
+pub mod probability;
+pub mod regions;
+pub mod registry;
+pub mod representation;
+pub mod rules;
+pub mod stats;
 pub mod version;
@@ -0,0 +1,110 @@
+//! Module defining the deckbuilding rules of the *Origins* of the [`CardRegion::Runeterra`] champions.
+//!
+//! A Runeterra champion takes up one of the regions of the deck, and lets the deck include the cards its Origin allows, regardless of their region.
+//!
+//! # Warning
+//!
+//! Card data does not describe the rules of Origins, so they are approximated with predicates over the [`Card`]s.
+//!
+//! Some of the predicates use card descriptions and subtypes, which are localized: they are only accurate for cards loaded in the [`ORIGINS_LOCALE`].
+//!
+//! The *Elder Dragon* Origin is not supported, as no champion bringing it is present in the card data: [`origin_of`] never returns [`CardRegion::ElderDragon`], and [`origin_predicate`] returns [`None`] for it.
+
+use crate::data::setbundle::card::Card;
+use crate::data::setbundle::keyword::CardKeyword;
+use crate::data::setbundle::r#type::CardType;
+use crate::data::setbundle::region::CardRegion;
+use crate::data::setbundle::speed::SpellSpeed;
+use crate::data::setbundle::supertype::CardSupertype;
+
+/// The locale the [`OriginPredicate`]s are written for: to check Origins accurately, cards should be loaded in it.
+pub const ORIGINS_LOCALE: &str = "en_us";
+
+/// A predicate checking whether a [`Card`] can be included in a deck thanks to an Origin.
+pub type OriginPredicate = fn(&Card) -> bool;
+
+/// The card codes of the Runeterra champions, and the Origin each of them brings.
+const ORIGIN_CHAMPIONS: [(&str, CardRegion); 10] = [
+    ("06RU001", CardRegion::Bard),
+    ("06RU002", CardRegion::Jhin),
+    ("06RU005", CardRegion::Kayn),
+    ("06RU006", CardRegion::Ryze),
+    ("06RU008", CardRegion::Jax),
+    ("06RU009", CardRegion::Varus),
+    ("06RU025", CardRegion::Evelynn),
+    ("06RU026", CardRegion::Aatrox),
+    ("07RU012", CardRegion::Neeko),
+    ("07RU015", CardRegion::PoroKing),
+];
+
+/// Get the Origin brought by the given [`Card`], or [`None`] if it is not a Runeterra champion.
+pub fn origin_of(card: &Card) -> Option<CardRegion> {
+    ORIGIN_CHAMPIONS
+        .iter()
+        .find(|(code, _)| *code == card.code.full)
+        .map(|(_, origin)| *origin)
+}
+
+/// Get the [`OriginPredicate`] of the given Origin, or [`None`] if the [`CardRegion`] is not an Origin.
+pub fn origin_predicate(origin: CardRegion) -> Option<OriginPredicate> {
+    match origin {
+        CardRegion::Aatrox => Some(|card| has_subtype(card, "DARKIN")),
+        CardRegion::Bard => Some(|card| mentions(card, "Chime")),
+        CardRegion::Evelynn => Some(|card| mentions(card, "Husk")),
+        CardRegion::Jax => Some(|card| card.r#type == CardType::Equipment || has_subtype(card, "WEAPONMASTER")),
+        CardRegion::Jhin => Some(|card| card.keywords.contains(&CardKeyword::Skill) || mentions(card, "Skill")),
+        CardRegion::Kayn => Some(|card| has_subtype(card, "CULTIST")),
+        CardRegion::Neeko => Some(|card| ["BIRD", "CAT", "DOG", "ELNUK", "FAE", "REPTILE", "SPIDER"].iter().any(|s| has_subtype(card, s))),
+        CardRegion::PoroKing => Some(|card| has_subtype(card, "PORO") || mentions(card, "Poro")),
+        CardRegion::Ryze => Some(|card| card.r#type == CardType::Spell && card.supertype != CardSupertype::Champion && (card.spell_speed == SpellSpeed::Burst || card.keywords.contains(&CardKeyword::Focus))),
+        CardRegion::Varus => Some(|card| card.keywords.contains(&CardKeyword::Attack) || mentions(card, "Attack:")),
+        _ => None,
+    }
+}
+
+/// Check whether the given [`Card`] can be included in a deck thanks to the given Origin.
+///
+/// The Runeterra champion bringing the Origin always fits it.
+pub fn fits_origin(origin: CardRegion, card: &Card) -> bool {
+    origin_of(card) == Some(origin) || origin_predicate(origin).is_some_and(|predicate| predicate(card))
+}
+
+/// Check whether the [`Card`] has the given subtype, ignoring capitalization.
+fn has_subtype(card: &Card, subtype: &str) -> bool {
+    card.subtypes.iter().any(|s| s.eq_ignore_ascii_case(subtype))
+}
+
+/// Check whether the description of the [`Card`] mentions the given text.
+fn mentions(card: &Card, text: &str) -> bool {
+    card.localized_description_text.contains(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::setbundle::code::CardCode;
+    use crate::data::setbundle::create_cardindex_from_wd;
+
+    #[test]
+    fn origin_champions() {
+        let index = create_cardindex_from_wd();
+        for (code, origin) in ORIGIN_CHAMPIONS {
+            let card = index.get(&CardCode::from(code.to_string())).expect("champion to exist");
+            assert_eq!(origin_of(card), Some(origin));
+            assert!(fits_origin(origin, card));
+            assert!(origin.is_origin());
+        }
+    }
+
+    #[test]
+    fn origin_predicates() {
+        let index = create_cardindex_from_wd();
+        let matching = |origin: CardRegion| index.values().filter(|c| c.collectible && fits_origin(origin, c)).count();
+
+        assert!(matching(CardRegion::Evelynn) > 1);
+        assert!(matching(CardRegion::Kayn) > 1);
+        assert!(matching(CardRegion::Jax) > 1);
+        assert!(origin_predicate(CardRegion::Demacia).is_none());
+        assert!(origin_predicate(CardRegion::ElderDragon).is_none());
+    }
+}
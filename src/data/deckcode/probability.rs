@@ -0,0 +1,286 @@
+//! Module computing the probability of drawing some cards of a [`Deck`], taking the mulligan into account.
+//!
+//! Probabilities are computed exactly with the [hypergeometric distribution](https://en.wikipedia.org/wiki/Hypergeometric_distribution), by enumerating every possible opening hand.
+
+use crate::data::deckcode::deck::Deck;
+use crate::data::setbundle::card::{Card, CardIndex};
+use crate::data::setbundle::code::CardCode;
+use std::collections::HashSet;
+
+/// Whether the player goes first or second in a game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TurnOrder {
+    /// The player has the attack token in the first round.
+    First,
+    /// The opponent has the attack token in the first round.
+    Second,
+}
+
+/// The rules determining how many cards a player draws during a game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DrawRules {
+    /// The number of cards in the opening hand, which can be mulliganed.
+    pub opening_hand: u32,
+
+    /// The number of cards drawn at the start of every round, including the first one.
+    pub draws_per_round: u32,
+
+    /// The number of additional cards drawn by the player going second.
+    pub extra_draws_going_second: u32,
+}
+
+impl DrawRules {
+    /// Get the number of cards seen after the mulligan, by the start of the given round.
+    pub fn draws_by(&self, round: u32, order: TurnOrder) -> u32 {
+        let extra = match order {
+            TurnOrder::First => 0,
+            TurnOrder::Second => self.extra_draws_going_second,
+        };
+
+        round * self.draws_per_round + extra
+    }
+}
+
+impl Default for DrawRules {
+    /// The rules of Legends of Runeterra, in which both players draw the same number of cards regardless of the [`TurnOrder`].
+    fn default() -> Self {
+        Self {
+            opening_hand: 4,
+            draws_per_round: 1,
+            extra_draws_going_second: 0,
+        }
+    }
+}
+
+/// The strategy used to mulligan the opening hand.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Mulligan {
+    /// Keep the whole opening hand.
+    KeepAll,
+    /// Keep only the cards with the given codes, and throw back every other card.
+    KeepOnly(HashSet<CardCode>),
+}
+
+impl Mulligan {
+    /// Check whether the card with the given code is kept in the opening hand.
+    pub fn keeps(&self, code: &CardCode) -> bool {
+        match self {
+            Mulligan::KeepAll => true,
+            Mulligan::KeepOnly(kept) => kept.contains(code),
+        }
+    }
+}
+
+/// A question about the cards a player will have drawn by a certain round.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DrawQuery {
+    /// The codes of the cards to look for; copies of any of them count towards [`DrawQuery::min_copies`].
+    pub targets: HashSet<CardCode>,
+
+    /// The minimum number of copies of the target cards to draw.
+    pub min_copies: u32,
+
+    /// The round by whose start the cards should be drawn, starting from 1.
+    ///
+    /// Round 0 considers only the opening hand after the mulligan.
+    pub round: u32,
+
+    /// Whether the player goes first or second.
+    pub order: TurnOrder,
+
+    /// The strategy used to mulligan the opening hand.
+    pub mulligan: Mulligan,
+}
+
+impl DrawQuery {
+    /// Ask for the chance to draw at least one of the given cards by the start of the given round, going first and keeping the whole opening hand.
+    pub fn any_of(targets: HashSet<CardCode>, round: u32) -> Self {
+        Self {
+            targets,
+            min_copies: 1,
+            round,
+            order: TurnOrder::First,
+            mulligan: Mulligan::KeepAll,
+        }
+    }
+
+    /// Compute the probability of the query being satisfied while playing the given [`Deck`].
+    pub fn probability(&self, deck: &Deck, rules: &DrawRules) -> f64 {
+        // Split the deck in four groups, depending on whether cards are targets, and whether they are kept
+        let mut groups = [0u32; 4];
+        for (code, quantity) in deck.contents.iter() {
+            let target = self.targets.contains(code);
+            let kept = self.mulligan.keeps(code);
+            groups[usize::from(!target) * 2 + usize::from(!kept)] += quantity;
+        }
+        let [target_kept, target_thrown, other_kept, other_thrown] = groups;
+
+        let total = deck.card_count();
+        let targets = target_kept + target_thrown;
+        let hand = rules.opening_hand.min(total);
+        let rest = total - hand;
+        let draws = rules.draws_by(self.round, self.order).min(rest);
+
+        let mut probability = 0.0;
+
+        for tk in 0..=target_kept.min(hand) {
+            for tt in 0..=target_thrown.min(hand - tk) {
+                for ok in 0..=other_kept.min(hand - tk - tt) {
+                    let ot = hand - tk - tt - ok;
+                    if ot > other_thrown {
+                        continue;
+                    }
+
+                    let hand_chance = choose(target_kept, tk) * choose(target_thrown, tt) * choose(other_kept, ok) * choose(other_thrown, ot) / choose(total, hand);
+                    if hand_chance == 0.0 {
+                        continue;
+                    }
+
+                    // Thrown cards are replaced with cards from the rest of the deck, and then shuffled back into it
+                    let thrown = tt + ot;
+                    let remaining_targets = targets - tk - tt;
+
+                    for replaced in 0..=remaining_targets.min(thrown) {
+                        let replace_chance = hypergeometric(rest, remaining_targets, thrown, replaced);
+                        if replace_chance == 0.0 {
+                            continue;
+                        }
+
+                        let in_hand = tk + replaced;
+                        let needed = self.min_copies.saturating_sub(in_hand);
+                        let deck_targets = remaining_targets - replaced + tt;
+
+                        let draw_chance = (needed..=deck_targets.min(draws))
+                            .map(|drawn| hypergeometric(rest, deck_targets, draws, drawn))
+                            .sum::<f64>();
+
+                        probability += hand_chance * replace_chance * draw_chance;
+                    }
+                }
+            }
+        }
+
+        probability.clamp(0.0, 1.0)
+    }
+}
+
+/// Get the codes of the cards of the [`Deck`] matching the given predicate, to be used as targets or kept cards.
+///
+/// Cards missing from the [`CardIndex`] never match.
+pub fn codes_matching(deck: &Deck, cards: &CardIndex, predicate: impl Fn(&Card) -> bool) -> HashSet<CardCode> {
+    deck.contents
+        .keys()
+        .filter(|code| code.to_card(cards).is_some_and(&predicate))
+        .cloned()
+        .collect()
+}
+
+/// Compute the number of ways to choose `k` elements out of `n`, as a floating point number.
+fn choose(n: u32, k: u32) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+
+    let k = k.min(n - k);
+    (0..k).fold(1.0, |acc, i| acc * f64::from(n - i) / f64::from(i + 1))
+}
+
+/// Compute the probability of drawing exactly `drawn` successes in `draws` draws without replacement, from a `population` containing `successes` successes.
+fn hypergeometric(population: u32, successes: u32, draws: u32, drawn: u32) -> f64 {
+    if drawn > successes || drawn > draws || draws > population || draws - drawn > population - successes {
+        return 0.0;
+    }
+
+    choose(successes, drawn) * choose(population - successes, draws - drawn) / choose(population, draws)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck;
+
+    fn codes(codes: &[&str]) -> HashSet<CardCode> {
+        codes.iter().map(|code| CardCode::from(code.to_string())).collect()
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{} is not close to {}", a, b);
+    }
+
+    #[test]
+    fn binomial_coefficients() {
+        assert_close(choose(40, 0), 1.0);
+        assert_close(choose(40, 4), 91390.0);
+        assert_close(choose(3, 4), 0.0);
+    }
+
+    #[test]
+    fn single_copy_without_mulligan() {
+        let d: Deck = deck!["01DE002": 1, "01DE012": 39];
+        let query = DrawQuery::any_of(codes(&["01DE002"]), 1);
+
+        // Five cards seen out of forty
+        assert_close(query.probability(&d, &DrawRules::default()), 5.0 / 40.0);
+    }
+
+    #[test]
+    fn three_copies_without_mulligan() {
+        let d: Deck = deck!["01DE002": 3, "01DE012": 37];
+        let query = DrawQuery::any_of(codes(&["01DE002"]), 0);
+
+        let miss = choose(37, 4) / choose(40, 4);
+        assert_close(query.probability(&d, &DrawRules::default()), 1.0 - miss);
+    }
+
+    #[test]
+    fn hard_mulligan_improves_odds() {
+        let d: Deck = deck!["01DE002": 3, "01DE012": 37];
+        let targets = codes(&["01DE002"]);
+
+        let keep = DrawQuery::any_of(targets.clone(), 3);
+        let hard = DrawQuery {
+            mulligan: Mulligan::KeepOnly(targets),
+            ..keep.clone()
+        };
+
+        let rules = DrawRules::default();
+        assert!(hard.probability(&d, &rules) > keep.probability(&d, &rules));
+    }
+
+    #[test]
+    fn mulligan_replacement_exact() {
+        let d: Deck = deck!["01DE002": 1, "01DE012": 7];
+        let rules = DrawRules { opening_hand: 1, draws_per_round: 1, extra_draws_going_second: 0 };
+        let query = DrawQuery {
+            mulligan: Mulligan::KeepOnly(codes(&["01DE002"])),
+            ..DrawQuery::any_of(codes(&["01DE002"]), 0)
+        };
+
+        // Either the target is in the opening hand, or it is the replacement of the thrown card
+        assert_close(query.probability(&d, &rules), 1.0 / 8.0 + 7.0 / 8.0 * 1.0 / 7.0);
+    }
+
+    #[test]
+    fn going_second() {
+        let d: Deck = deck!["01DE002": 1, "01DE012": 39];
+        let rules = DrawRules { extra_draws_going_second: 1, ..DrawRules::default() };
+        let query = DrawQuery {
+            order: TurnOrder::Second,
+            ..DrawQuery::any_of(codes(&["01DE002"]), 1)
+        };
+
+        assert_close(query.probability(&d, &rules), 6.0 / 40.0);
+    }
+
+    #[test]
+    fn impossible_and_certain() {
+        let d: Deck = deck!["01DE002": 2, "01DE012": 2];
+        let rules = DrawRules::default();
+
+        let three = DrawQuery { min_copies: 3, ..DrawQuery::any_of(codes(&["01DE002"]), 5) };
+        assert_close(three.probability(&d, &rules), 0.0);
+
+        let two = DrawQuery { min_copies: 2, ..DrawQuery::any_of(codes(&["01DE002"]), 0) };
+        assert_close(two.probability(&d, &rules), 1.0);
+    }
+}
@@ -0,0 +1,283 @@
+//! Module defining the [`RegionAssignment`] of a [`Deck`](crate::data::deckcode::deck::Deck), which describes every way its cards can be distributed among regions.
+
+use crate::data::deckcode::origins::{fits_origin, origin_of};
+use crate::data::setbundle::card::Card;
+use crate::data::setbundle::code::CardCode;
+use crate::data::setbundle::region::CardRegion;
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+
+/// Every minimal set of regions a group of [`Card`]s fits in, and how each card relates to them.
+///
+/// Runeterra champions count as the Origin they bring, and every other card may count as any of those Origins it [fits in](crate::data::deckcode::origins::fits_origin).
+///
+/// Other cards belonging to [`CardRegion::Runeterra`] fit in any set of regions, while cards belonging only to [`CardRegion::Unsupported`] fit in none.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegionAssignment {
+    /// The maximum number of regions considered.
+    pub limit: usize,
+
+    /// Every set of at most `limit` regions the cards fit in, which has no subset the cards also fit in.
+    ///
+    /// Solutions are sorted by size, then by their regions.
+    pub solutions: Vec<HashSet<CardRegion>>,
+
+    /// For each region, the cards which belong only to it, and therefore force it to be part of every solution.
+    pub forcing: HashMap<CardRegion, Vec<CardCode>>,
+
+    /// For each card, the regions it may be counted as in at least one solution.
+    ///
+    /// Cards belonging to [`CardRegion::Runeterra`] without bringing an Origin are always counted as it.
+    pub card_regions: HashMap<CardCode, HashSet<CardRegion>>,
+}
+
+impl RegionAssignment {
+    /// Find every minimal set of at most `limit` regions the given [`Card`]s fit in.
+    ///
+    /// Regions sets are enumerated as bitmasks over the regions of the cards, so the time required grows linearly with the number of cards, and polynomially with the number of distinct regions.
+    pub fn solve<'c>(cards: impl IntoIterator<Item = &'c Card>, limit: usize) -> Self {
+        let cards = cards
+            .into_iter()
+            .unique_by(|card| &card.code)
+            .sorted_by(|a, b| a.code.cmp(&b.code))
+            .collect_vec();
+
+        let origins = cards
+            .iter()
+            .filter_map(|card| origin_of(card))
+            .unique()
+            .collect_vec();
+
+        let (free, bound): (Vec<&Card>, Vec<&Card>) = cards
+            .into_iter()
+            .partition(|card| origin_of(card).is_none() && card.regions.contains(&CardRegion::Runeterra));
+
+        // Runeterra champions count as their Origin, and other cards may count as any Origin they fit in
+        let bound_regions = bound
+            .iter()
+            .map(|card| match origin_of(card) {
+                Some(origin) => vec![origin],
+                None => card.regions
+                    .iter()
+                    .copied()
+                    .chain(origins.iter().copied().filter(|origin| fits_origin(*origin, card)))
+                    .collect_vec(),
+            })
+            .collect_vec();
+
+        let candidates: Vec<CardRegion> = bound_regions
+            .iter()
+            .flatten()
+            .copied()
+            .filter(|region| *region != CardRegion::Unsupported)
+            .unique()
+            .sorted()
+            .collect();
+
+        let mask_of = |regions: &[CardRegion]| -> u32 {
+            candidates
+                .iter()
+                .enumerate()
+                .filter(|(_, candidate)| regions.contains(candidate))
+                .fold(0, |mask, (index, _)| mask | (1 << index))
+        };
+
+        let card_masks = bound_regions
+            .iter()
+            .map(|regions| mask_of(regions))
+            .collect_vec();
+
+        let mut solutions: Vec<u32> = vec![];
+        for size in 0..=limit.min(candidates.len()) {
+            for combination in (0..candidates.len()).combinations(size) {
+                let set = combination.iter().fold(0, |mask, index| mask | (1 << index));
+
+                let is_superset = solutions.iter().any(|solution| solution & set == *solution);
+                let covers_all = card_masks.iter().all(|card| card & set != 0);
+
+                if !is_superset && covers_all {
+                    solutions.push(set);
+                }
+            }
+        }
+
+        let regions_of = |mask: u32| -> HashSet<CardRegion> {
+            candidates
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| mask & (1 << index) != 0)
+                .map(|(_, region)| *region)
+                .collect()
+        };
+
+        let mut forcing: HashMap<CardRegion, Vec<CardCode>> = HashMap::new();
+        for (card, mask) in bound.iter().zip(card_masks.iter()) {
+            if mask.count_ones() == 1 {
+                let region = candidates[mask.trailing_zeros() as usize];
+                forcing.entry(region).or_default().push(card.code.clone());
+            }
+        }
+
+        let mut card_regions: HashMap<CardCode, HashSet<CardRegion>> = HashMap::new();
+        for card in free.iter() {
+            card_regions.insert(card.code.clone(), HashSet::from([CardRegion::Runeterra]));
+        }
+        for (card, mask) in bound.iter().zip(card_masks.iter()) {
+            let usable = solutions
+                .iter()
+                .fold(0, |usable, solution| usable | (mask & solution));
+            card_regions.insert(card.code.clone(), regions_of(usable));
+        }
+
+        Self {
+            limit,
+            solutions: solutions.into_iter().map(regions_of).collect(),
+            forcing,
+            card_regions,
+        }
+    }
+
+    /// Whether the cards fit in at least one set of regions.
+    pub fn is_solvable(&self) -> bool {
+        !self.solutions.is_empty()
+    }
+
+    /// Whether the cards fit in more than one minimal set of regions.
+    pub fn is_ambiguous(&self) -> bool {
+        self.solutions.len() > 1
+    }
+
+    /// The first, and therefore smallest, set of regions the cards fit in.
+    pub fn first(&self) -> Option<&HashSet<CardRegion>> {
+        self.solutions.first()
+    }
+
+    /// The regions which are part of every solution.
+    pub fn required_regions(&self) -> HashSet<CardRegion> {
+        self.solutions
+            .iter()
+            .cloned()
+            .reduce(|a, b| a.intersection(&b).copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Whether the card with the given code may be counted as more than one region.
+    pub fn is_card_ambiguous(&self, code: &CardCode) -> bool {
+        self.card_regions
+            .get(code)
+            .is_some_and(|regions| regions.len() > 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::setbundle::create_cardindex_from_wd;
+
+    fn solve(codes: &[&str], limit: usize) -> RegionAssignment {
+        let index = create_cardindex_from_wd();
+        let cards = codes
+            .iter()
+            .map(|code| index.get(&CardCode::from(code.to_string())).expect("card to exist"))
+            .cloned()
+            .collect_vec();
+        RegionAssignment::solve(cards.iter(), limit)
+    }
+
+    #[test]
+    fn single_region() {
+        let assignment = solve(&["01DE002", "01DE012"], 2);
+        assert_eq!(assignment.solutions, vec![HashSet::from([CardRegion::Demacia])]);
+        assert!(!assignment.is_ambiguous());
+        assert_eq!(assignment.forcing[&CardRegion::Demacia].len(), 2);
+    }
+
+    #[test]
+    fn too_many_regions() {
+        let assignment = solve(&["01DE002", "01FR003", "01IO003"], 2);
+        assert!(!assignment.is_solvable());
+    }
+
+    #[test]
+    fn multi_region_ambiguity() {
+        let index = create_cardindex_from_wd();
+        let multi = index
+            .values()
+            .filter(|card| card.collectible && card.regions.len() == 2)
+            .filter(|card| !card.regions.contains(&CardRegion::Runeterra) && !card.regions.contains(&CardRegion::Unsupported))
+            .sorted_by(|a, b| a.code.cmp(&b.code))
+            .collect_vec();
+
+        let card = multi.first().expect("a dual-region card to exist");
+        let assignment = RegionAssignment::solve([*card], 2);
+
+        assert_eq!(assignment.solutions.len(), 2);
+        assert!(assignment.is_ambiguous());
+        assert!(assignment.is_card_ambiguous(&card.code));
+        assert!(assignment.forcing.is_empty());
+        assert!(assignment.required_regions().is_empty());
+    }
+
+    #[test]
+    fn many_distinct_multi_region_cards() {
+        let index = create_cardindex_from_wd();
+        let multi = index
+            .values()
+            .filter(|card| card.collectible && card.regions.len() >= 2 && !card.regions.contains(&CardRegion::Runeterra))
+            .sorted_by(|a, b| a.code.cmp(&b.code))
+            .take(40)
+            .collect_vec();
+
+        let forced = index.get(&CardCode::from("01DE002".to_string())).expect("card to exist");
+        let cards = multi.iter().copied().chain([forced]).collect_vec();
+
+        let regions = cards.iter().flat_map(|card| card.regions.iter().copied()).unique().sorted().collect_vec();
+        let smallest = minimal_covers(&cards, &regions, regions.len()).iter().map(HashSet::len).min().expect("the cards to fit in all their regions");
+        for limit in [2, 3, smallest - 1, smallest, smallest + 1, regions.len()] {
+            let assignment = RegionAssignment::solve(cards.iter().copied(), limit);
+            let expected = minimal_covers(&cards, &regions, limit);
+
+            assert_eq!(assignment.is_solvable(), !expected.is_empty());
+            assert_eq!(assignment.solutions.len(), expected.len());
+            assert!(expected.iter().all(|cover| assignment.solutions.contains(cover)));
+            assert!(assignment.solutions.windows(2).all(|pair| pair[0].len() <= pair[1].len()));
+            assert_eq!(assignment.forcing, HashMap::from([(CardRegion::Demacia, vec![forced.code.clone()])]));
+        }
+
+        assert!(!RegionAssignment::solve(cards.iter().copied(), smallest - 1).is_solvable());
+        assert!(RegionAssignment::solve(cards.iter().copied(), smallest).is_solvable());
+    }
+
+    /// Find by brute force every set of at most `limit` of the given regions which covers all the given cards, and has no strict subset which does too.
+    fn minimal_covers(cards: &[&Card], regions: &[CardRegion], limit: usize) -> Vec<HashSet<CardRegion>> {
+        let covers = |set: &HashSet<CardRegion>| cards.iter().all(|card| card.regions.iter().any(|region| set.contains(region)));
+
+        regions
+            .iter()
+            .copied()
+            .powerset()
+            .map(|set| set.into_iter().collect::<HashSet<CardRegion>>())
+            .filter(|set| set.len() <= limit && covers(set))
+            .filter(|set| !set.iter().any(|removed| covers(&set.iter().copied().filter(|region| region != removed).collect())))
+            .collect()
+    }
+
+    #[test]
+    fn origin_counts_as_region() {
+        let index = create_cardindex_from_wd();
+        let jax = index.get(&CardCode::from("06RU008".to_string())).expect("Jax to exist");
+        let equipment = index
+            .values()
+            .filter(|card| card.collectible && card.r#type == crate::data::setbundle::r#type::CardType::Equipment)
+            .find(|card| card.regions == vec![CardRegion::Bilgewater])
+            .expect("a Bilgewater equipment to exist");
+        let demacia = index.get(&CardCode::from("01DE002".to_string())).expect("card to exist");
+
+        let without_jax = RegionAssignment::solve([equipment, demacia], 1);
+        assert!(!without_jax.is_solvable());
+
+        let with_jax = RegionAssignment::solve([jax, equipment, demacia], 2);
+        assert_eq!(with_jax.solutions, vec![HashSet::from([CardRegion::Jax, CardRegion::Demacia])]);
+        assert_eq!(with_jax.forcing[&CardRegion::Jax], vec![jax.code.clone()]);
+    }
+}
@@ -0,0 +1,346 @@
+//! Module defining the [`CardIdRegistry`], which maps the numeric ids used in deck codes to [`CardSet`]s and [`CardRegion`]s.
+//!
+//! A process-wide registry is available through [`registry`] and [`registry_mut`]; it starts with all ids known at the time of writing, and can be extended at runtime as new expansions are released.
+
+use crate::data::corebundle::globals::LocalizedGlobalsIndexes;
+use crate::data::deckcode::version::DeckCodeVersion;
+use crate::data::setbundle::card::Card;
+use crate::data::setbundle::region::CardRegion;
+use crate::data::setbundle::set::CardSet;
+use lazy_static::lazy_static;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// A [`CardSet`] known to a [`CardIdRegistry`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RegisteredSet {
+    /// The numeric id of the set, as used in deck codes.
+    pub id: u32,
+
+    /// The two-digit short code of the set, as used in [`CardCode`](crate::data::setbundle::code::CardCode)s.
+    pub code: String,
+
+    /// The [`CardSet`] variant, or [`CardSet::Unsupported`] if the set is not known to this version of the crate.
+    pub set: CardSet,
+}
+
+/// A [`CardRegion`] known to a [`CardIdRegistry`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RegisteredRegion {
+    /// The numeric id of the region, as used in deck codes.
+    pub id: u32,
+
+    /// The two-letter short code of the region, as used in [`CardCode`](crate::data::setbundle::code::CardCode)s.
+    pub code: String,
+
+    /// The [`CardRegion`] variant, or [`CardRegion::Unsupported`] if the region is not known to this version of the crate.
+    pub region: CardRegion,
+
+    /// The minimum [`DeckCodeVersion`] able to encode cards of this region.
+    pub version: DeckCodeVersion,
+}
+
+/// A registry of the numeric ids, short codes and enum variants of [`CardSet`]s and [`CardRegion`]s.
+///
+/// Lookups return the **first** matching entry, so that ambiguous ids such as `6` (shared by [`CardSet::Worldwalker`] and [`CardSet::TheDarkinSaga`]) resolve to the entry registered first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CardIdRegistry {
+    /// The registered sets, in registration order.
+    sets: Vec<RegisteredSet>,
+
+    /// The registered regions, in registration order.
+    regions: Vec<RegisteredRegion>,
+}
+
+impl CardIdRegistry {
+    /// Create a [`CardIdRegistry`] containing no sets and no regions.
+    pub fn empty() -> Self {
+        Self {
+            sets: vec![],
+            regions: vec![],
+        }
+    }
+
+    /// Create a [`CardIdRegistry`] containing all the sets and regions known to this version of the crate.
+    ///
+    /// Region ids and versions are taken from [RiotGames/LoRDeckCodes](https://github.com/RiotGames/LoRDeckCodes).
+    pub fn builtin() -> Self {
+        let mut registry = Self::empty();
+
+        registry.register_set(1, CardSet::Foundations);
+        registry.register_set(2, CardSet::RisingTides);
+        registry.register_set(3, CardSet::CallOfTheMountain);
+        registry.register_set(4, CardSet::EmpiresOfTheAscended);
+        registry.register_set(5, CardSet::BeyondTheBandlewood);
+        registry.register_set(6, CardSet::Worldwalker);
+        registry.register_set(6, CardSet::TheDarkinSaga);
+        registry.register_set(7, CardSet::GloryInNavori);
+        registry.register_set(7, CardSet::HeartOfTheHuntress);
+        registry.register_set(8, CardSet::FatesVoyage);
+        registry.register_set(9, CardSet::DreamlitPaths);
+
+        registry.register_region(0, "DE", CardRegion::Demacia, DeckCodeVersion::V1);
+        registry.register_region(1, "FR", CardRegion::Freljord, DeckCodeVersion::V1);
+        registry.register_region(2, "IO", CardRegion::Ionia, DeckCodeVersion::V1);
+        registry.register_region(3, "NX", CardRegion::Noxus, DeckCodeVersion::V1);
+        registry.register_region(4, "PZ", CardRegion::PiltoverZaun, DeckCodeVersion::V1);
+        registry.register_region(5, "SI", CardRegion::ShadowIsles, DeckCodeVersion::V1);
+        registry.register_region(6, "BW", CardRegion::Bilgewater, DeckCodeVersion::V2);
+        registry.register_region(7, "SH", CardRegion::Shurima, DeckCodeVersion::V3);
+        registry.register_region(9, "MT", CardRegion::Targon, DeckCodeVersion::V2);
+        registry.register_region(10, "BC", CardRegion::BandleCity, DeckCodeVersion::V4);
+        registry.register_region(12, "RU", CardRegion::Runeterra, DeckCodeVersion::V5);
+
+        registry
+    }
+
+    /// Get an [`Iterator`] over all registered sets.
+    pub fn sets(&self) -> impl Iterator<Item = &RegisteredSet> {
+        self.sets.iter()
+    }
+
+    /// Get an [`Iterator`] over all registered regions.
+    pub fn regions(&self) -> impl Iterator<Item = &RegisteredRegion> {
+        self.regions.iter()
+    }
+
+    /// Find the first registered set with the given numeric id.
+    pub fn set_by_id(&self, id: u32) -> Option<&RegisteredSet> {
+        self.sets.iter().find(|s| s.id == id)
+    }
+
+    /// Find the first registered set with the given short code.
+    pub fn set_by_code(&self, code: &str) -> Option<&RegisteredSet> {
+        self.sets.iter().find(|s| s.code == code)
+    }
+
+    /// Find the registered set with the given [`CardSet`] variant.
+    ///
+    /// [`CardSet::Unsupported`] never matches anything.
+    pub fn set_by_variant(&self, set: CardSet) -> Option<&RegisteredSet> {
+        match set {
+            CardSet::Unsupported => None,
+            _ => self.sets.iter().find(|s| s.set == set),
+        }
+    }
+
+    /// Find the first registered region with the given numeric id.
+    pub fn region_by_id(&self, id: u32) -> Option<&RegisteredRegion> {
+        self.regions.iter().find(|r| r.id == id)
+    }
+
+    /// Find the first registered region with the given short code.
+    pub fn region_by_code(&self, code: &str) -> Option<&RegisteredRegion> {
+        self.regions.iter().find(|r| r.code == code)
+    }
+
+    /// Find the registered region with the given [`CardRegion`] variant.
+    ///
+    /// [`CardRegion::Unsupported`] never matches anything.
+    pub fn region_by_variant(&self, region: CardRegion) -> Option<&RegisteredRegion> {
+        match region {
+            CardRegion::Unsupported => None,
+            _ => self.regions.iter().find(|r| r.region == region),
+        }
+    }
+
+    /// Register a set with the given numeric id.
+    ///
+    /// If the pair is already registered, nothing happens; if the id was registered as [`CardSet::Unsupported`], the entry is upgraded to the given variant.
+    ///
+    /// Returns `true` if the registry was changed.
+    pub fn register_set(&mut self, id: u32, set: CardSet) -> bool {
+        if self.sets.iter().any(|s| s.id == id && s.set == set) {
+            return false;
+        }
+
+        if set != CardSet::Unsupported {
+            if let Some(entry) = self.sets.iter_mut().find(|s| s.id == id && s.set == CardSet::Unsupported) {
+                entry.set = set;
+                return true;
+            }
+        }
+        else if self.set_by_id(id).is_some() {
+            return false;
+        }
+
+        self.sets.push(RegisteredSet {
+            id,
+            code: format!("{:02}", id),
+            set,
+        });
+        true
+    }
+
+    /// Register a region with the given numeric id, short code, and minimum [`DeckCodeVersion`].
+    ///
+    /// If a region with the same id or the same short code is already registered, nothing happens.
+    ///
+    /// Returns `true` if the registry was changed.
+    pub fn register_region(&mut self, id: u32, code: &str, region: CardRegion, version: DeckCodeVersion) -> bool {
+        if self.region_by_id(id).is_some() || self.region_by_code(code).is_some() {
+            return false;
+        }
+
+        self.regions.push(RegisteredRegion {
+            id,
+            code: code.to_string(),
+            region,
+            version,
+        });
+        true
+    }
+
+    /// Register all sets of the given [`LocalizedGlobalsIndexes`] that are missing from the registry.
+    ///
+    /// The numeric id of each set is determined from the digits in its `nameRef`, such as `Set7b` → `7`; sets without digits, such as [`CardSet::Events`], are skipped.
+    /// Sets unknown to this version of the crate are registered as [`CardSet::Unsupported`].
+    ///
+    /// Regions are not affected, as Data Dragon does not specify their numeric ids: use [`CardIdRegistry::register_region`] instead.
+    ///
+    /// Returns the number of sets that were registered.
+    pub fn extend_from_globals(&mut self, globals: &LocalizedGlobalsIndexes) -> usize {
+        let mut count = 0;
+
+        for set in globals.sets.values().chain(globals.unsupported_sets.values()) {
+            if self.set_by_variant(set.set).is_some() {
+                continue;
+            }
+
+            if let Some(id) = set.id() {
+                if self.register_set(id, set.set) {
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+
+    /// Register the sets of all the given [`Card`]s that are missing from the registry, using the set segment of their [`CardCode`](crate::data::setbundle::code::CardCode)s as id.
+    ///
+    /// [`CardSet::Events`] cards are skipped, as they have the code of the set they were released in.
+    ///
+    /// Returns the number of sets that were registered.
+    pub fn extend_from_cards<'c>(&mut self, cards: impl IntoIterator<Item = &'c Card>) -> usize {
+        let mut count = 0;
+
+        for card in cards {
+            if card.set == CardSet::Events || !card.code.is_valid() {
+                continue;
+            }
+
+            if let Ok(id) = card.code.set().parse::<u32>() {
+                if self.register_set(id, card.set) {
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+}
+
+/// The default [`CardIdRegistry`] is the [builtin](CardIdRegistry::builtin) one.
+impl Default for CardIdRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+lazy_static! {
+    /// The process-wide [`CardIdRegistry`].
+    static ref REGISTRY: RwLock<CardIdRegistry> = RwLock::new(CardIdRegistry::builtin());
+}
+
+/// Acquire read access to the process-wide [`CardIdRegistry`].
+///
+/// Used by [`Deck`](crate::data::deckcode::deck::Deck) encoding and decoding, and by the short code and id conversions of [`CardSet`] and [`CardRegion`].
+///
+/// # Panics
+///
+/// If the lock was poisoned by a panic during [`registry_mut`].
+pub fn registry() -> RwLockReadGuard<'static, CardIdRegistry> {
+    REGISTRY.read().expect("CardIdRegistry lock to not be poisoned")
+}
+
+/// Acquire write access to the process-wide [`CardIdRegistry`], for example to extend it with newly loaded data.
+///
+/// # Example
+///
+/// ```rust
+/// use patched_porobot::data::deckcode::registry::registry_mut;
+/// use patched_porobot::data::setbundle::create_cardindex_from_wd;
+///
+/// let index = create_cardindex_from_wd();
+/// registry_mut().extend_from_cards(index.values());
+/// ```
+///
+/// # Panics
+///
+/// If the lock was poisoned by a panic during another [`registry_mut`].
+pub fn registry_mut() -> RwLockWriteGuard<'static, CardIdRegistry> {
+    REGISTRY.write().expect("CardIdRegistry lock to not be poisoned")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::corebundle::set::LocalizedCardSet;
+
+    #[test]
+    fn builtin_ambiguous_set_resolves_to_first() {
+        let registry = CardIdRegistry::builtin();
+        assert_eq!(registry.set_by_id(6).unwrap().set, CardSet::Worldwalker);
+        assert_eq!(registry.set_by_variant(CardSet::TheDarkinSaga).unwrap().id, 6);
+        assert_eq!(registry.set_by_variant(CardSet::HeartOfTheHuntress).unwrap().code, "07");
+    }
+
+    #[test]
+    fn builtin_regions() {
+        let registry = CardIdRegistry::builtin();
+        assert_eq!(registry.region_by_id(12).unwrap().region, CardRegion::Runeterra);
+        assert_eq!(registry.region_by_code("MT").unwrap().id, 9);
+        assert!(registry.region_by_id(8).is_none());
+        assert!(registry.region_by_variant(CardRegion::Unsupported).is_none());
+    }
+
+    #[test]
+    fn register_unsupported_then_upgrade() {
+        let mut registry = CardIdRegistry::empty();
+        assert!(registry.register_set(42, CardSet::Unsupported));
+        assert!(!registry.register_set(42, CardSet::Unsupported));
+        assert_eq!(registry.set_by_code("42").unwrap().set, CardSet::Unsupported);
+        assert!(registry.register_set(42, CardSet::Events));
+        assert_eq!(registry.set_by_id(42).unwrap().set, CardSet::Events);
+    }
+
+    #[test]
+    fn extend_from_globals() {
+        let globals = crate::data::corebundle::create_globalindexes_from_wd();
+        let mut registry = CardIdRegistry::empty();
+        registry.extend_from_globals(&globals);
+        assert_eq!(registry.set_by_variant(CardSet::FatesVoyage).unwrap().id, 8);
+        assert_eq!(registry.set_by_variant(CardSet::HeartOfTheHuntress).unwrap().id, 7);
+        assert!(registry.set_by_variant(CardSet::Events).is_none());
+    }
+
+    #[test]
+    fn extend_from_unsupported_globals() {
+        let mut globals = crate::data::corebundle::create_globalindexes_from_wd();
+        let future: LocalizedCardSet = serde_json::from_str(r#"{"iconAbsolutePath": "", "name": "Future", "nameRef": "Set42"}"#).unwrap();
+        globals.unsupported_sets.insert(future.id().unwrap(), future);
+
+        let mut registry = CardIdRegistry::empty();
+        registry.extend_from_globals(&globals);
+        assert_eq!(registry.set_by_code("42").unwrap().set, CardSet::Unsupported);
+    }
+
+    #[test]
+    fn extend_from_cards() {
+        let index = crate::data::setbundle::create_cardindex_from_wd();
+        let mut registry = CardIdRegistry::empty();
+        registry.extend_from_cards(index.values());
+        assert_eq!(registry.set_by_id(1).unwrap().set, CardSet::Foundations);
+        assert_eq!(registry.set_by_id(8).unwrap().set, CardSet::FatesVoyage);
+    }
+}
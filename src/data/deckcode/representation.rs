@@ -0,0 +1,188 @@
+//! Module defining the [`serde`] representations of a [`Deck`].
+//!
+//! A [`Deck`] can be represented either:
+//!
+//! - as its deck code string, such as `"CEAAAABIAEAAE"`;
+//! - as a map of card codes to quantities, such as `{"01DE002": 40}`, with the card codes sorted.
+//!
+//! [`Deck`] serializes to its deck code by default, and deserializes from either representation; use [`as_code`] or [`as_map`] with `#[serde(with = "...")]` to select one explicitly.
+//!
+//! Since it has to guess the representation, the default deserializer only works with self-describing formats, such as JSON.
+//!
+//! # Example
+//!
+//! ```rust
+//! use patched_porobot::deck;
+//! use patched_porobot::data::deckcode::deck::Deck;
+//!
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Config {
+//!     main: Deck,
+//!     #[serde(with = "patched_porobot::data::deckcode::representation::as_map")]
+//!     sideboard: Deck,
+//! }
+//!
+//! let config = Config {
+//!     main: deck!["01DE002": 40],
+//!     sideboard: deck!["01DE002": 3],
+//! };
+//!
+//! let json = serde_json::to_string(&config).unwrap();
+//! assert_eq!(json, r#"{"main":"CEAAAABIAEAAE","sideboard":{"01DE002":3}}"#);
+//! ```
+
+use crate::data::deckcode::deck::Deck;
+use crate::data::deckcode::format::DeckCodeFormat;
+use crate::data::setbundle::code::CardCode;
+use serde::de::{Error, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Formatter;
+
+/// Represent a [`Deck`] as its deck code string, in the [`DeckCodeFormat::F1`] format.
+pub mod as_code {
+    use super::*;
+
+    /// Serialize the [`Deck`] as its deck code.
+    pub fn serialize<S: Serializer>(deck: &Deck, serializer: S) -> Result<S::Ok, S::Error> {
+        let code = deck
+            .to_code(DeckCodeFormat::F1)
+            .map_err(serde::ser::Error::custom)?;
+
+        serializer.serialize_str(&code)
+    }
+
+    /// Deserialize a [`Deck`] from its deck code.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Deck, D::Error> {
+        let code = String::deserialize(deserializer)?;
+
+        Deck::from_code(&code).map_err(D::Error::custom)
+    }
+}
+
+/// Represent a [`Deck`] as a map of card codes to quantities, with the card codes sorted.
+pub mod as_map {
+    use super::*;
+
+    /// Serialize the [`Deck`] as a map sorted by card code.
+    pub fn serialize<S: Serializer>(deck: &Deck, serializer: S) -> Result<S::Ok, S::Error> {
+        deck.contents
+            .iter()
+            .collect::<BTreeMap<&CardCode, &u32>>()
+            .serialize(serializer)
+    }
+
+    /// Deserialize a [`Deck`] from a map of card codes to quantities, rejecting duplicate cards and zero quantities.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Deck, D::Error> {
+        deserializer.deserialize_map(DeckMapVisitor)
+    }
+}
+
+/// A [`Visitor`] accepting only the map representation of a [`Deck`].
+struct DeckMapVisitor;
+
+impl<'de> Visitor<'de> for DeckMapVisitor {
+    type Value = Deck;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        write!(formatter, "a map of card codes to quantities")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut contents = HashMap::new();
+
+        while let Some((code, quantity)) = map.next_entry::<CardCode, u32>()? {
+            if quantity == 0 {
+                return Err(A::Error::custom(format!("card {} has zero copies", code.full)));
+            }
+            if contents.insert(code.clone(), quantity).is_some() {
+                return Err(A::Error::custom(format!("card {} appears more than once", code.full)));
+            }
+        }
+
+        Ok(Deck { contents })
+    }
+}
+
+/// A [`Visitor`] accepting both representations of a [`Deck`].
+struct DeckVisitor;
+
+impl<'de> Visitor<'de> for DeckVisitor {
+    type Value = Deck;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        write!(formatter, "a deck code or a map of card codes to quantities")
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        Deck::from_code(v).map_err(E::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        DeckMapVisitor.visit_map(map)
+    }
+}
+
+impl Serialize for Deck {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        as_code::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Deck {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(DeckVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Mapped {
+        #[serde(with = "as_map")]
+        deck: Deck,
+    }
+
+    #[test]
+    fn code_round_trip() {
+        let deck: Deck = deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA");
+
+        let json = serde_json::to_string(&deck).unwrap();
+        assert_eq!(json, r#""CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA""#);
+        assert_eq!(serde_json::from_str::<Deck>(&json).unwrap(), deck);
+    }
+
+    #[test]
+    fn map_round_trip() {
+        let mapped = Mapped { deck: deck!["02BW003": 1, "01DE002": 2, "01DE001": 3] };
+
+        let json = serde_json::to_string(&mapped).unwrap();
+        assert_eq!(json, r#"{"deck":{"01DE001":3,"01DE002":2,"02BW003":1}}"#);
+        assert_eq!(serde_json::from_str::<Mapped>(&json).unwrap(), mapped);
+    }
+
+    #[test]
+    fn default_accepts_map() {
+        let deck = serde_json::from_str::<Deck>(r#"{"01DE002": 40}"#).unwrap();
+        assert_eq!(deck, deck!["01DE002": 40]);
+    }
+
+    #[test]
+    fn invalid_representations() {
+        assert!(serde_json::from_str::<Deck>(r#""NOT A DECK CODE""#).is_err());
+        assert!(serde_json::from_str::<Deck>(r#"{"01DE002": 1, "01DE002": 2}"#).is_err());
+        assert!(serde_json::from_str::<Deck>("40").is_err());
+        assert!(serde_json::from_str::<Deck>(r#"{"01DE002": 0}"#).is_err());
+        assert!(serde_json::to_string(&deck!["01DE002": 0]).is_err());
+    }
+
+    #[test]
+    fn invalid_maps() {
+        assert!(serde_json::from_str::<Mapped>(r#"{"deck":{"01DE002": 1, "01DE002": 2}}"#).is_err());
+        assert!(serde_json::from_str::<Mapped>(r#"{"deck":{"01DE002": 0}}"#).is_err());
+        assert!(serde_json::from_str::<Mapped>(r#"{"deck":"CEAAAABIAEAAE"}"#).is_err());
+    }
+}
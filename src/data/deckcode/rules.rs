@@ -0,0 +1,321 @@
+//! Module defining the [`FormatRules`] a [`Deck`](crate::data::deckcode::deck::Deck) can be checked against, and the [`FormatRegistry`] collecting them.
+//!
+//! A process-wide registry is available through [`format_registry`] and [`format_registry_mut`]; it starts with the rules of all built-in formats, and can be extended at runtime with custom or event formats.
+
+use crate::data::deckcode::legality::LegalityViolation;
+use crate::data::setbundle::card::{Card, CardIndex};
+use crate::data::setbundle::code::CardCode;
+use crate::data::setbundle::format::CardFormat;
+use crate::data::setbundle::rarity::CardRarity;
+use lazy_static::lazy_static;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// A restriction on the mana cost of the cards allowed in a format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CostRule {
+    /// Cards of any cost are allowed.
+    Any,
+    /// Only cards with an even cost are allowed.
+    Even,
+    /// Only cards with an odd cost are allowed.
+    Odd,
+    /// Only cards costing at most the given amount of mana are allowed.
+    AtMost(u64),
+}
+
+impl CostRule {
+    /// Check whether a card with the given cost is allowed by this rule.
+    pub fn allows(&self, cost: u64) -> bool {
+        match self {
+            CostRule::Any => true,
+            CostRule::Even => cost % 2 == 0,
+            CostRule::Odd => cost % 2 != 0,
+            CostRule::AtMost(max) => cost <= *max,
+        }
+    }
+}
+
+/// The constraints a [`Deck`](crate::data::deckcode::deck::Deck) has to respect to be legal in a format.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormatRules {
+    /// The name of the format, used to identify it in a [`FormatRegistry`].
+    pub name: String,
+
+    /// The [`CardFormat`] all cards in the deck must be tagged with, or [`None`] if cards are not checked for tags.
+    pub card_format: Option<CardFormat>,
+
+    /// The exact number of cards the deck must have.
+    pub deck_size: u32,
+
+    /// The maximum number of copies of a single card.
+    pub copies_limit: u32,
+
+    /// The maximum number of champion cards, or [`None`] if champions are not limited.
+    pub champions_limit: Option<u32>,
+
+    /// The maximum number of regions the cards of the deck may belong to.
+    pub regions_limit: usize,
+
+    /// The [`CardRarity`]s allowed in the deck, or [`None`] if cards of any rarity are allowed.
+    pub rarities: Option<Vec<CardRarity>>,
+
+    /// The restriction on the mana cost of the cards in the deck.
+    pub cost: CostRule,
+}
+
+impl FormatRules {
+    /// The rules of the *Standard* format.
+    pub fn standard() -> Self {
+        Self {
+            name: "Standard".to_string(),
+            card_format: Some(CardFormat::Standard),
+            deck_size: 40,
+            copies_limit: 3,
+            champions_limit: Some(6),
+            regions_limit: 2,
+            rarities: None,
+            cost: CostRule::Any,
+        }
+    }
+
+    /// The rules of the *Eternal* format.
+    pub fn eternal() -> Self {
+        Self {
+            name: "Eternal".to_string(),
+            card_format: Some(CardFormat::Eternal),
+            ..Self::standard()
+        }
+    }
+
+    /// The rules of the *Singleton* format.
+    ///
+    /// Cards are not tagged with [`CardFormat::Singleton`] in the data, so the *Eternal* card pool is used instead.
+    pub fn singleton() -> Self {
+        Self {
+            name: "Singleton".to_string(),
+            copies_limit: 1,
+            regions_limit: 3,
+            ..Self::eternal()
+        }
+    }
+
+    /// The rules of the *Unlimited Champions* format, which uses the *Eternal* card pool.
+    pub fn unlimited_champions() -> Self {
+        Self {
+            name: "Unlimited Champions".to_string(),
+            champions_limit: None,
+            ..Self::eternal()
+        }
+    }
+
+    /// The rules of the *Commons Only* format, in which only common cards and champions are allowed.
+    pub fn commons_only() -> Self {
+        Self {
+            name: "Commons Only".to_string(),
+            card_format: Some(CardFormat::CommonsOnly),
+            rarities: Some(vec![CardRarity::Common, CardRarity::Champion]),
+            ..Self::standard()
+        }
+    }
+
+    /// The rules of the *Even-cost Cards* format, which uses the *Eternal* card pool.
+    ///
+    /// Only a handful of cards are tagged with [`CardFormat::EvenCostCards`] in the data, so the costs of the cards are checked instead.
+    pub fn even_cost_cards() -> Self {
+        Self {
+            name: "Even-cost Cards".to_string(),
+            cost: CostRule::Even,
+            ..Self::eternal()
+        }
+    }
+
+    /// Get the built-in rules of the given [`CardFormat`], or [`None`] if the format is not supported.
+    pub fn builtin(format: CardFormat) -> Option<Self> {
+        match format {
+            CardFormat::Standard => Some(Self::standard()),
+            CardFormat::Singleton => Some(Self::singleton()),
+            CardFormat::Eternal => Some(Self::eternal()),
+            CardFormat::CommonsOnly => Some(Self::commons_only()),
+            CardFormat::EvenCostCards => Some(Self::even_cost_cards()),
+            _ => None,
+        }
+    }
+
+    /// Check whether the given [`Card`] is tagged with the [`CardFormat`] required by these rules.
+    pub fn allows_format_of(&self, card: &Card) -> bool {
+        self.card_format.map_or(true, |format| card.formats.contains(&format))
+    }
+
+    /// Check whether the [`CardRarity`] of the given [`Card`] is allowed by these rules.
+    ///
+    /// The rarity is normalized with [`Card::crafting_rarity`], so that all champions have [`CardRarity::Champion`].
+    pub fn allows_rarity_of(&self, card: &Card) -> bool {
+        self.rarities.as_ref().map_or(true, |rarities| rarities.contains(&card.crafting_rarity()))
+    }
+
+    /// Check whether the mana cost of the given [`Card`] is allowed by these rules.
+    pub fn allows_cost_of(&self, card: &Card) -> bool {
+        self.cost.allows(card.cost)
+    }
+
+    /// List the rules the card with the given code does not respect by itself, regardless of the rest of the deck.
+    pub fn card_violations(&self, code: &CardCode, cards: &CardIndex) -> Vec<LegalityViolation> {
+        let card = match code.to_card(cards) {
+            Some(card) => card,
+            None => return vec![LegalityViolation::UnknownCard(code.clone())],
+        };
+
+        let mut violations = vec![];
+
+        if let (false, Some(format)) = (self.allows_format_of(card), self.card_format) {
+            violations.push(LegalityViolation::CardNotAllowed { card: code.clone(), format });
+        }
+        if !self.allows_rarity_of(card) {
+            violations.push(LegalityViolation::RarityNotAllowed { card: code.clone(), rarity: card.crafting_rarity() });
+        }
+        if !self.allows_cost_of(card) {
+            violations.push(LegalityViolation::CostNotAllowed { card: code.clone(), cost: card.cost });
+        }
+
+        violations
+    }
+}
+
+/// A registry of the [`FormatRules`] known to the application, identified by their name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FormatRegistry {
+    /// The registered formats, in registration order.
+    formats: Vec<FormatRules>,
+}
+
+impl FormatRegistry {
+    /// Create a [`FormatRegistry`] containing no formats.
+    pub fn empty() -> Self {
+        Self { formats: vec![] }
+    }
+
+    /// Create a [`FormatRegistry`] containing the rules of all the formats known to this version of the crate.
+    pub fn builtin() -> Self {
+        let mut registry = Self::empty();
+
+        registry.register(FormatRules::standard());
+        registry.register(FormatRules::eternal());
+        registry.register(FormatRules::unlimited_champions());
+        registry.register(FormatRules::singleton());
+        registry.register(FormatRules::commons_only());
+        registry.register(FormatRules::even_cost_cards());
+
+        registry
+    }
+
+    /// Iterate over all the registered [`FormatRules`], in registration order.
+    pub fn formats(&self) -> impl Iterator<Item = &FormatRules> {
+        self.formats.iter()
+    }
+
+    /// Get the [`FormatRules`] with the given name.
+    pub fn by_name(&self, name: &str) -> Option<&FormatRules> {
+        self.formats.iter().find(|f| f.name == name)
+    }
+
+    /// Register the given [`FormatRules`].
+    ///
+    /// Returns `false` without changing anything if rules with the same name are already registered.
+    pub fn register(&mut self, rules: FormatRules) -> bool {
+        if self.by_name(&rules.name).is_some() {
+            return false;
+        }
+
+        self.formats.push(rules);
+        true
+    }
+
+    /// Unregister the [`FormatRules`] with the given name, returning them if they were registered.
+    pub fn unregister(&mut self, name: &str) -> Option<FormatRules> {
+        let index = self.formats.iter().position(|f| f.name == name)?;
+        Some(self.formats.remove(index))
+    }
+}
+
+impl Default for FormatRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+lazy_static! {
+    /// The process-wide [`FormatRegistry`].
+    static ref FORMATS: RwLock<FormatRegistry> = RwLock::new(FormatRegistry::builtin());
+}
+
+/// Get a read guard to the process-wide [`FormatRegistry`].
+pub fn format_registry() -> RwLockReadGuard<'static, FormatRegistry> {
+    FORMATS.read().expect("FormatRegistry lock to not be poisoned")
+}
+
+/// Get a write guard to the process-wide [`FormatRegistry`], to register custom formats.
+///
+/// # Example
+///
+/// ```rust
+/// use patched_porobot::data::deckcode::rules::{format_registry, format_registry_mut, CostRule, FormatRules};
+///
+/// format_registry_mut().register(FormatRules {
+///     name: "Cheap Eternal".to_string(),
+///     cost: CostRule::AtMost(3),
+///     ..FormatRules::eternal()
+/// });
+///
+/// assert!(format_registry().by_name("Cheap Eternal").is_some());
+/// ```
+pub fn format_registry_mut() -> RwLockWriteGuard<'static, FormatRegistry> {
+    FORMATS.write().expect("FormatRegistry lock to not be poisoned")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_for_every_format() {
+        for format in [CardFormat::Standard, CardFormat::Singleton, CardFormat::Eternal, CardFormat::CommonsOnly, CardFormat::EvenCostCards] {
+            assert!(FormatRules::builtin(format).is_some());
+        }
+        assert!(FormatRules::builtin(CardFormat::Unsupported).is_none());
+    }
+
+    #[test]
+    fn cost_rules() {
+        assert!(CostRule::Any.allows(7));
+        assert!(CostRule::Even.allows(0));
+        assert!(!CostRule::Even.allows(3));
+        assert!(CostRule::Odd.allows(3));
+        assert!(CostRule::AtMost(3).allows(3));
+        assert!(!CostRule::AtMost(3).allows(4));
+    }
+
+    #[test]
+    fn champion_rarity_is_normalized() {
+        let index = crate::data::setbundle::create_cardindex_from_wd();
+        let mut braum = index[&CardCode::from("01FR009".to_string())].clone();
+        braum.rarity = CardRarity::Epic;
+
+        assert!(FormatRules::commons_only().allows_rarity_of(&braum));
+        assert!(!FormatRules { rarities: Some(vec![CardRarity::Epic]), ..FormatRules::eternal() }.allows_rarity_of(&braum));
+    }
+
+    #[test]
+    fn register_and_unregister() {
+        let mut registry = FormatRegistry::builtin();
+        assert!(!registry.register(FormatRules::eternal()));
+
+        let custom = FormatRules {
+            name: "Highlander".to_string(),
+            ..FormatRules::singleton()
+        };
+        assert!(registry.register(custom.clone()));
+        assert_eq!(registry.by_name("Highlander"), Some(&custom));
+        assert_eq!(registry.unregister("Highlander"), Some(custom));
+        assert!(registry.by_name("Highlander").is_none());
+    }
+}
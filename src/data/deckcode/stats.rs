@@ -0,0 +1,152 @@
+//! Module defining [`DeckStats`], a summary of the contents of a [`Deck`].
+
+use crate::data::deckcode::deck::Deck;
+use crate::data::setbundle::card::CardIndex;
+use crate::data::setbundle::code::CardCode;
+use crate::data::setbundle::keyword::CardKeyword;
+use crate::data::setbundle::r#type::CardType;
+use crate::data::setbundle::region::CardRegion;
+use crate::data::setbundle::subtype::CardSubtype;
+use crate::data::setbundle::supertype::CardSupertype;
+use itertools::Itertools;
+use std::collections::HashMap;
+
+/// The number of buckets of [`DeckStats::mana_curve`]; the last bucket contains all cards costing that much or more.
+pub const MANA_CURVE_BUCKETS: usize = 8;
+
+/// Statistics about the cards contained in a [`Deck`].
+///
+/// All counts take the number of copies of each card into account; cards missing from the [`CardIndex`] are only counted in [`DeckStats::unknown_cards`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeckStats {
+    /// The number of cards for each mana cost, from 0 to [`MANA_CURVE_BUCKETS`] - 1 or more.
+    pub mana_curve: [u32; MANA_CURVE_BUCKETS],
+
+    /// The number of cards of each [`CardType`].
+    pub types: HashMap<CardType, u32>,
+
+    /// The champions in the deck with their number of copies, sorted by card code.
+    pub champions: Vec<(CardCode, u32)>,
+
+    /// The number of cards belonging to each [`CardRegion`].
+    ///
+    /// Cards belonging to multiple regions are counted once for each of them.
+    pub regions: HashMap<CardRegion, u32>,
+
+    /// The number of cards having each [`CardKeyword`].
+    pub keywords: HashMap<CardKeyword, u32>,
+
+    /// The number of cards having each [`CardSubtype`].
+    pub subtypes: HashMap<CardSubtype, u32>,
+
+    /// The average mana cost of the known cards, or [`None`] if no cards are known.
+    pub average_cost: Option<f64>,
+
+    /// The number of cards missing from the [`CardIndex`].
+    pub unknown_cards: u32,
+}
+
+impl DeckStats {
+    /// Compute the statistics of the given [`Deck`].
+    pub fn of(deck: &Deck, cards: &CardIndex) -> Self {
+        let mut stats = Self {
+            mana_curve: [0; MANA_CURVE_BUCKETS],
+            types: HashMap::new(),
+            champions: vec![],
+            regions: HashMap::new(),
+            keywords: HashMap::new(),
+            subtypes: HashMap::new(),
+            average_cost: None,
+            unknown_cards: 0,
+        };
+
+        let mut known_cards: u32 = 0;
+        let mut total_cost: u64 = 0;
+
+        for (code, quantity) in deck.contents.iter().sorted() {
+            let card = match code.to_card(cards) {
+                Some(card) => card,
+                None => {
+                    stats.unknown_cards += quantity;
+                    continue;
+                }
+            };
+
+            known_cards += quantity;
+            total_cost += card.cost * u64::from(*quantity);
+
+            let bucket = usize::try_from(card.cost).unwrap_or(usize::MAX).min(MANA_CURVE_BUCKETS - 1);
+            stats.mana_curve[bucket] += quantity;
+
+            *stats.types.entry(card.r#type).or_default() += quantity;
+
+            if card.supertype == CardSupertype::Champion {
+                stats.champions.push((code.clone(), *quantity));
+            }
+
+            for region in card.regions.iter().unique() {
+                *stats.regions.entry(*region).or_default() += quantity;
+            }
+            for keyword in card.keywords.iter().unique() {
+                *stats.keywords.entry(*keyword).or_default() += quantity;
+            }
+            for subtype in card.subtypes.iter().unique() {
+                *stats.subtypes.entry(subtype.clone()).or_default() += quantity;
+            }
+        }
+
+        if known_cards > 0 {
+            stats.average_cost = Some(total_cost as f64 / f64::from(known_cards));
+        }
+
+        stats
+    }
+
+    /// Get the number of cards of the given [`CardType`].
+    pub fn count_of_type(&self, r#type: CardType) -> u32 {
+        self.types.get(&r#type).copied().unwrap_or(0)
+    }
+
+    /// Get the [`CardKeyword`]s of the deck, sorted from the most to the least frequent.
+    ///
+    /// Keywords with the same frequency are sorted in the order they are declared in [`CardKeyword`].
+    pub fn keywords_by_frequency(&self) -> Vec<(CardKeyword, u32)> {
+        self.keywords
+            .iter()
+            .map(|(keyword, count)| (*keyword, *count))
+            .sorted_by(|(a_kw, a_count), (b_kw, b_count)| b_count.cmp(a_count).then(a_kw.cmp(b_kw)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::setbundle::create_cardindex_from_wd;
+    use crate::deck;
+
+    #[test]
+    fn stats_of_deck() {
+        let index = create_cardindex_from_wd();
+        let deck: Deck = deck!("CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA");
+        let stats = DeckStats::of(&deck, &index);
+
+        assert_eq!(stats.mana_curve.iter().sum::<u32>(), 40);
+        assert_eq!(stats.types.values().sum::<u32>(), 40);
+        assert_eq!(stats.champions.iter().map(|(_, copies)| copies).sum::<u32>(), deck.champions_count(&index));
+        assert_eq!(stats.unknown_cards, 0);
+        assert!(stats.average_cost.is_some());
+        assert!(stats.regions.contains_key(&CardRegion::Ionia));
+    }
+
+    #[test]
+    fn stats_of_unknown() {
+        let index = create_cardindex_from_wd();
+        let deck: Deck = deck!["99XX999": 3];
+        let stats = DeckStats::of(&deck, &index);
+
+        assert_eq!(stats.unknown_cards, 3);
+        assert_eq!(stats.average_cost, None);
+        assert_eq!(stats.mana_curve, [0; MANA_CURVE_BUCKETS]);
+    }
+}
@@ -1,6 +1,7 @@
 //! Module defining the [`DeckCodeVersion`] enum and [`DeckCodeVersioned`] trait.
 
 use crate::data::deckcode::deck::Deck;
+use crate::data::deckcode::registry::registry;
 use crate::data::setbundle::code::CardCode;
 use crate::data::setbundle::region::CardRegion;
 
@@ -59,50 +60,32 @@ pub trait DeckCodeVersioned {
     fn min_deckcode_version(&self) -> Option<DeckCodeVersion>;
 }
 
+/// [`CardRegion`]'s version is determined by the process-wide [`CardIdRegistry`](crate::data::deckcode::registry::CardIdRegistry).
 impl DeckCodeVersioned for CardRegion {
     fn min_deckcode_version(&self) -> Option<DeckCodeVersion> {
-        match self {
-            CardRegion::Noxus => Some(DeckCodeVersion::V1),
-            CardRegion::Demacia => Some(DeckCodeVersion::V1),
-            CardRegion::Freljord => Some(DeckCodeVersion::V1),
-            CardRegion::ShadowIsles => Some(DeckCodeVersion::V1),
-            CardRegion::PiltoverZaun => Some(DeckCodeVersion::V1),
-            CardRegion::Ionia => Some(DeckCodeVersion::V1),
-
-            CardRegion::Targon => Some(DeckCodeVersion::V2),
-            CardRegion::Bilgewater => Some(DeckCodeVersion::V2),
-
-            CardRegion::Shurima => Some(DeckCodeVersion::V3),
-
-            CardRegion::BandleCity => Some(DeckCodeVersion::V4),
-
-            CardRegion::Runeterra => Some(DeckCodeVersion::V5),
-
-            _ => None,
-        }
+        registry()
+            .region_by_variant(*self)
+            .map(|r| r.version)
     }
 }
 
 /// [`CardCode`]'s version is the maximum version of its components.
 impl DeckCodeVersioned for CardCode {
     fn min_deckcode_version(&self) -> Option<DeckCodeVersion> {
-        CardRegion::from_code(self.region()).min_deckcode_version()
+        registry()
+            .region_by_code(self.region())
+            .map(|r| r.version)
     }
 }
 
 /// [`Deck`]'s version is the maximum version of all its [`CardCode`]s.
+///
+/// Like Riot's reference encoder, an empty [`Deck`] is considered to require [`DeckCodeVersion::V1`].
 impl DeckCodeVersioned for Deck {
     fn min_deckcode_version(&self) -> Option<DeckCodeVersion> {
-        let codes = self.contents.keys();
-        let versions = codes.map(|cc| cc.min_deckcode_version());
-
-        // TODO: I'm almost sure this can be optimized, but it's 5 AM
-        for version in versions.clone() {
-            version?;
-        }
-
-        let versions = versions.map(|v| v.unwrap());
-
-        versions.max()
+        self.contents
+            .keys()
+            .map(|cc| cc.min_deckcode_version())
+            .try_fold(DeckCodeVersion::V1, |max, version| Some(max.max(version?)))
     }
 }
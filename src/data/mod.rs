@@ -3,6 +3,8 @@
 //! [Data Dragon]: https://developer.riotgames.com/docs/lor#data-dragon
 
 pub mod anybundle;
+pub mod cache;
 pub mod corebundle;
+pub mod database;
 pub mod deckcode;
 pub mod setbundle;
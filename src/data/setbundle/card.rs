@@ -13,6 +13,9 @@ use crate::data::setbundle::supertype::CardSupertype;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use crate::data::setbundle::format::CardFormat;
+use crate::data::corebundle::globals::LocalizedGlobalsIndexes;
+use crate::data::corebundle::set::LocalizedCardSet;
+use crate::data::deckcode::registry::registry;
 
 /// A single Legends of Runeterra card, as represented in a `set*.json` file.
 ///
@@ -160,11 +163,33 @@ impl Card {
         self.associated_card_codes.iter().map(|r| index.get(r))
     }
 
+    /// Get the [LocalizedCardSet] of the card from the given [LocalizedGlobalsIndexes].
+    ///
+    /// Cards of sets unknown to this version of the crate are looked up in [LocalizedGlobalsIndexes::unsupported_sets] by the id the process-wide [CardIdRegistry](crate::data::deckcode::registry::CardIdRegistry) associates with the set segment of their code.
+    pub fn localized_set<'g>(&self, globals: &'g LocalizedGlobalsIndexes) -> Option<&'g LocalizedCardSet> {
+        match self.set {
+            CardSet::Unsupported => registry()
+                .set_by_code(self.code.set())
+                .and_then(|registered| globals.unsupported_sets.get(&registered.id)),
+            set => set.localized(&globals.sets),
+        }
+    }
+
     /// Get a reference to the first [CardArt] of the card.
     ///
-    /// Equivalent to calling [Card].[art](Card::art).[get(0)]([T]::get).
+    /// Equivalent to calling [Card].[art](Card::art).[first()]([T]::first).
     pub fn main_art(&self) -> Option<&CardArt> {
-        self.art.get(0)
+        self.art.first()
+    }
+
+    /// Get the [`CardRarity`] used to craft the card.
+    ///
+    /// Champions are tagged with their own rarity only through [`Card::supertype`], so they are treated as [`CardRarity::Champion`] regardless of [`Card::rarity`].
+    pub fn crafting_rarity(&self) -> CardRarity {
+        match self.supertype {
+            CardSupertype::Champion => CardRarity::Champion,
+            _ => self.rarity,
+        }
     }
 }
 
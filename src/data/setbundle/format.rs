@@ -50,6 +50,19 @@ impl CardFormat {
     }
 }
 
+impl From<&CardFormat> for &'static str {
+    fn from(format: &CardFormat) -> Self {
+        match format {
+            CardFormat::Standard => "Standard",
+            CardFormat::Singleton => "Singleton",
+            CardFormat::Eternal => "Eternal",
+            CardFormat::CommonsOnly => "Commons Only",
+            CardFormat::EvenCostCards => "Even-cost Cards",
+            CardFormat::Unsupported => "Unknown",
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::CardFormat;
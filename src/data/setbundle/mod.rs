@@ -3,9 +3,12 @@
 //! [Data Dragon]: https://developer.riotgames.com/docs/lor#data-dragon
 //! [Set Bundle]: https://developer.riotgames.com/docs/lor#data-dragon_set-bundles
 
+use super::anybundle::archive::{is_archive, BundleArchive};
 use super::anybundle::metadata::BundleMetadata;
-use crate::data::anybundle::outcomes::{LoadingError, LoadingResult};
+use crate::data::anybundle::outcomes::{BundleFailure, BundlesResult, LoadingError, LoadingResult, PartialLoad};
+use crate::data::cache::version_url;
 use std::fs::File;
+use std::io::{Read, Seek};
 use std::path::{Path, PathBuf};
 
 pub mod art;
@@ -60,6 +63,46 @@ impl SetBundle {
         })
     }
 
+    /// Load a Set Bundle from either a directory or a zip archive, depending on the extension of the given path.
+    pub fn load_any(path: &Path) -> LoadingResult<Self> {
+        match is_archive(path) {
+            true => Self::load_zip(path),
+            false => Self::load(path),
+        }
+    }
+
+    /// Load a Set Bundle zip archive, such as `set1-lite-en_us.zip`, to create a [SetBundle] instance.
+    pub fn load_zip(archive_path: &Path) -> LoadingResult<Self> {
+        log::debug!("Loading SetBundle from archive: {}", archive_path.as_os_str().to_string_lossy());
+
+        Self::from_archive(BundleArchive::open(archive_path)?)
+    }
+
+    /// Read a Set Bundle zip archive from the given reader, such as a [std::io::Cursor] over an in-memory buffer, to create a [SetBundle] instance.
+    pub fn from_zip<R: Read + Seek>(reader: R) -> LoadingResult<Self> {
+        Self::from_archive(BundleArchive::new(reader)?)
+    }
+
+    /// Read the cards of a Set Bundle from a [BundleArchive].
+    ///
+    /// The archive name may differ from the name of the data file, so the only JSON file in the `[locale]/data` directory is used.
+    fn from_archive<R: Read + Seek>(mut archive: BundleArchive<R>) -> LoadingResult<Self> {
+        let metadata = archive.metadata()?;
+        let locale = metadata.locale().ok_or(LoadingError::GettingLocale)?;
+
+        let data_path = archive
+            .data_files(locale)
+            .into_iter()
+            .next()
+            .ok_or(LoadingError::ReadingArchive(zip::result::ZipError::FileNotFound))?;
+
+        let cards = archive.read_json::<Vec<card::Card>>(&data_path)?;
+
+        Ok(SetBundle {
+            cards,
+        })
+    }
+
     /// Fetch from `base_url` the Set Bundle data of the given `set` with the given `locale`.
     pub async fn fetch(client: &reqwest::Client, base_url: &str, locale: &str, set: &str) -> LoadingResult<Self> {
         let url = format!("{}/{}/{}/data/{}-{}.json", base_url, set, locale, set, locale);
@@ -82,8 +125,152 @@ impl SetBundle {
 }
 
 
+/// Create a [`card::CardIndex`] from the set bundles in the given paths, skipping the ones which cannot be loaded.
+///
+/// Each path may be either a directory or a zip archive (see [`SetBundle::load_any`]).
+///
+/// The returned [`PartialLoad`] identifies each failed bundle by its path.
+pub fn create_cardindex_from_paths_partial(paths: impl Iterator<Item = PathBuf>) -> PartialLoad<card::CardIndex> {
+    let mut index = card::CardIndex::new();
+    let mut failures = vec![];
+
+    for path in paths {
+        match SetBundle::load_any(&path) {
+            Ok(set) => {
+                for card in set.cards {
+                    index.insert(card.code.clone(), card);
+                }
+            }
+            Err(error) => {
+                log::warn!("Could not load SetBundle from {}: {}", path.to_string_lossy(), error);
+                failures.push(BundleFailure { bundle: path.to_string_lossy().to_string(), error });
+            }
+        }
+    };
+
+    PartialLoad { value: index, failures }
+}
+
+/// Create a [`card::CardIndex`] from the set bundles in the given paths, failing with every [`BundleFailure`] if any of them cannot be loaded.
+pub fn try_create_cardindex_from_paths(paths: impl Iterator<Item = PathBuf>) -> BundlesResult<card::CardIndex> {
+    create_cardindex_from_paths_partial(paths).into_result()
+}
+
+/// Create a [`card::CardIndex`] from set bundles in the given paths.
+///
+/// # Panics
+///
+/// If any of the required files cannot be loaded (see [`SetBundle::load_any`]).
+pub fn create_cardindex_from_paths(paths: impl Iterator<Item = PathBuf>) -> card::CardIndex {
+    try_create_cardindex_from_paths(paths).expect("to be able to load SetBundle")
+}
+
+/// The paths of the set bundles in the current working directory, either directories or zip archives matching the [glob] `./data/set*-*`.
+fn wd_set_paths() -> impl Iterator<Item = PathBuf> {
+    glob::glob("./data/set*-*")
+        .expect("glob to be a valid glob")
+        .filter_map(Result::ok)
+}
+
+/// Create a [`card::CardIndex`] from the set bundles in the current working directory, skipping the ones which cannot be loaded.
+///
+/// See [`create_cardindex_from_wd`] and [`create_cardindex_from_paths_partial`].
+pub fn create_cardindex_from_wd_partial() -> PartialLoad<card::CardIndex> {
+    create_cardindex_from_paths_partial(wd_set_paths())
+}
+
+/// Create a [`card::CardIndex`] from the set bundles in the current working directory, failing with every [`BundleFailure`] if any of them cannot be loaded.
+///
+/// See [`create_cardindex_from_wd`].
+pub fn try_create_cardindex_from_wd() -> BundlesResult<card::CardIndex> {
+    try_create_cardindex_from_paths(wd_set_paths())
+}
+
+/// Create a [`card::CardIndex`] from set bundles in the current working directory.
+///
+/// This function tries to load data from any directory or zip archive matching the [glob] `./data/set*-*`.
+///
+/// # Panics
+///
+/// See [`create_cardindex_from_paths`].
+pub fn create_cardindex_from_wd() -> card::CardIndex {
+    create_cardindex_from_paths(wd_set_paths())
+}
+
+/// Create a [`card::CardIndex`] from the given version of Data Dragon, such as `5_9_0` or `latest`, skipping the sets which cannot be fetched.
+///
+/// The returned [`PartialLoad`] identifies each failed bundle by its set code.
+pub async fn create_cardindex_from_dd_partial(version: &str, locale: &str, known_set_codes: impl Iterator<Item = &str>) -> PartialLoad<card::CardIndex> {
+    let client = reqwest::Client::new();
+    let mut index = card::CardIndex::new();
+    let mut failures = vec![];
+
+    for set_code in known_set_codes {
+        let set = match version_url(version) {
+            Ok(base_url) => SetBundle::fetch(&client, &base_url, locale, set_code).await,
+            Err(error) => Err(error),
+        };
+
+        match set {
+            Ok(set) => {
+                for card in set.cards {
+                    index.insert(card.code.clone(), card);
+                }
+            }
+            Err(error) => {
+                log::warn!("Could not fetch SetBundle {}: {}", set_code, error);
+                failures.push(BundleFailure { bundle: set_code.to_string(), error });
+            }
+        }
+    };
+
+    PartialLoad { value: index, failures }
+}
+
+/// Create a [`card::CardIndex`] from the latest known data in Data Dragon, skipping the sets which cannot be fetched.
+///
+/// The returned [`PartialLoad`] identifies each failed bundle by its set code.
+pub async fn create_cardindex_from_dd_latest_partial(locale: &str, known_set_codes: impl Iterator<Item = &str>) -> PartialLoad<card::CardIndex> {
+    create_cardindex_from_dd_partial("latest", locale, known_set_codes).await
+}
+
+/// Create a [`card::CardIndex`] from the latest known data in Data Dragon, failing with every [`BundleFailure`] if any set cannot be fetched.
+pub async fn try_create_cardindex_from_dd_latest(locale: &str, known_set_codes: impl Iterator<Item = &str>) -> BundlesResult<card::CardIndex> {
+    create_cardindex_from_dd_latest_partial(locale, known_set_codes).await.into_result()
+}
+
+/// Create a [`card::CardIndex`] from the latest known data in Data Dragon.
+///
+/// This function tries to load data from `https://dd.b.pvp.net/latest`.
+///
+/// # Panics
+///
+/// If any of the sets cannot be fetched.
+pub async fn create_cardindex_from_dd_latest(locale: &str, known_set_codes: impl Iterator<Item = &str>) -> card::CardIndex {
+    try_create_cardindex_from_dd_latest(locale, known_set_codes).await
+        .expect("to be able to fetch set bundle")
+}
+
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_load() {
+        let paths = vec![PathBuf::from("./data/set1-en_us"), PathBuf::from("./data/set0-xx_xx")];
+
+        let partial = create_cardindex_from_paths_partial(paths.clone().into_iter());
+        assert!(!partial.is_complete());
+        assert!(!partial.value.is_empty());
+        assert_eq!(partial.failures.len(), 1);
+        assert_eq!(partial.failures[0].bundle, "./data/set0-xx_xx");
+        assert!(matches!(partial.failures[0].error, LoadingError::OpeningFile(_)));
+
+        assert!(try_create_cardindex_from_paths(paths.into_iter()).is_err());
+        assert!(try_create_cardindex_from_wd().is_ok());
+    }
+
     macro_rules! test_fetch {
         ( $id:ident, $version:literal, $locale:literal, $set:literal ) => {
             #[tokio::test]
@@ -132,55 +319,3 @@ mod tests {
     test_fetch!(test_fetch_latest_en_us_set8, "latest", "en_us", "set8");
     test_fetch!(test_fetch_latest_en_us_set9, "latest", "en_us", "set9");
 }
-
-
-/// Create a [`card::CardIndex`] from set bundles in the given paths.
-///
-/// # Panics
-///
-/// If any of the required files cannot be loaded (see [`SetBundle::load`]).
-pub fn create_cardindex_from_paths(paths: impl Iterator<Item = PathBuf>) -> card::CardIndex {
-    let mut index = card::CardIndex::new();
-    for path in paths {
-        let set = SetBundle::load(&path).expect("to be able to load SetBundle");
-        for card in set.cards {
-            index.insert(card.code.clone(), card);
-        }
-    };
-    index
-}
-
-/// Create a [`card::CardIndex`] from set bundles in the current working directory.
-///
-/// This function tries to load data from any directory matching the [glob] `./data/set*-*`.
-///
-/// # Panics
-///
-/// See [`create_cardindex_from_paths`].
-pub fn create_cardindex_from_wd() -> card::CardIndex {
-    let paths = glob::glob("./data/set*-*")
-        .expect("glob to be a valid glob")
-        .filter_map(Some)
-        .filter_map(Result::ok);
-
-    create_cardindex_from_paths(paths)
-}
-
-/// Create a [`card::CardIndex`] from the latest known data in Data Dragon.
-///
-/// This function tries to load data from `https://dd.b.pvp.net/latest`.
-pub async fn create_cardindex_from_dd_latest(locale: &str, known_set_codes: impl Iterator<Item = &str>) -> card::CardIndex {
-    let client = reqwest::Client::new();
-    let mut index = card::CardIndex::new();
-
-    for set_code in known_set_codes {
-        let set = SetBundle::fetch(&client, "https://dd.b.pvp.net/latest", locale, set_code).await
-            .expect("to be able to fetch set bundle");
-
-        for card in set.cards {
-            index.insert(card.code.clone(), card);
-        }
-    };
-
-    index
-}
\ No newline at end of file
@@ -51,6 +51,19 @@ impl CardRarity {
         }
     }
 
+    /// Get the number of shards required to craft a card of this [`CardRarity`].
+    ///
+    /// Returns [Option::None] for rarities which cannot be crafted.
+    pub fn shard_cost(&self) -> Option<u32> {
+        match self {
+            CardRarity::Common => Some(100),
+            CardRarity::Rare => Some(300),
+            CardRarity::Epic => Some(1200),
+            CardRarity::Champion => Some(3000),
+            _ => None,
+        }
+    }
+
     /// Get the color associated with this [`CardRarity`].
     ///
     /// Used for example to determine the color of the Discord embed.
@@ -66,6 +79,19 @@ impl CardRarity {
     }
 }
 
+impl From<&CardRarity> for &'static str {
+    fn from(rarity: &CardRarity) -> Self {
+        match rarity {
+            CardRarity::None => "None",
+            CardRarity::Common => "Common",
+            CardRarity::Rare => "Rare",
+            CardRarity::Epic => "Epic",
+            CardRarity::Champion => "Champion",
+            CardRarity::Unsupported => "Unknown",
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::CardRarity;
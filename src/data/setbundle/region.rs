@@ -1,12 +1,13 @@
 //! Module defining [CardRegion].
 
 use crate::data::corebundle::region::{LocalizedCardRegion, LocalizedCardRegionIndex};
+use crate::data::deckcode::registry::registry;
 
 /// A region to which [Card](super::card::Card)s can belong to.
 ///
 /// Since more regions might be added in the future, especially Origin ones, this enum is [non_exaustive](https://doc.rust-lang.org/reference/attributes/type_system.html#the-non_exhaustive-attribute).
 #[non_exhaustive]
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize)]
 pub enum CardRegion {
     /// Noxus.
     Noxus,
@@ -73,44 +74,22 @@ impl CardRegion {
         hm.get(self)
     }
 
-    /// Get the [`CardRegion`] from its short code.
+    /// Get the [`CardRegion`] from its short code, using the process-wide [`CardIdRegistry`](crate::data::deckcode::registry::CardIdRegistry).
     ///
     /// If no region has the specified short code, this will return [`CardRegion::Unsupported`].
     pub fn from_code(value: &str) -> Self {
-        match value {
-            "DE" => Self::Demacia,
-            "FR" => Self::Freljord,
-            "IO" => Self::Ionia,
-            "NX" => Self::Noxus,
-            "PZ" => Self::PiltoverZaun,
-            "SI" => Self::ShadowIsles,
-            "BW" => Self::Bilgewater,
-            "SH" => Self::Shurima,
-            "MT" => Self::Targon,
-            "BC" => Self::BandleCity,
-            "RU" => Self::Runeterra,
-            _ => Self::Unsupported,
-        }
+        registry()
+            .region_by_code(value)
+            .map_or(Self::Unsupported, |r| r.region)
     }
 
-    /// Get the short code of this [`CardRegion`].
+    /// Get the short code of this [`CardRegion`], using the process-wide [`CardIdRegistry`](crate::data::deckcode::registry::CardIdRegistry).
     ///
     /// If the region has no short code, it will return [`None`].
     pub fn to_code(&self) -> Option<String> {
-        match self {
-            Self::Demacia => Some("DE".to_string()),
-            Self::Freljord => Some("FR".to_string()),
-            Self::Ionia => Some("IO".to_string()),
-            Self::Noxus => Some("NX".to_string()),
-            Self::PiltoverZaun => Some("PZ".to_string()),
-            Self::ShadowIsles => Some("SI".to_string()),
-            Self::Bilgewater => Some("BW".to_string()),
-            Self::Shurima => Some("SH".to_string()),
-            Self::Targon => Some("MT".to_string()),
-            Self::BandleCity => Some("BC".to_string()),
-            Self::Runeterra => Some("RU".to_string()),
-            _ => None,
-        }
+        registry()
+            .region_by_variant(*self)
+            .map(|r| r.code.clone())
     }
 
     /// Get the long code of this [`CardRegion`].
@@ -131,11 +110,30 @@ impl CardRegion {
             Self::Targon => Some("Targon"),
             Self::BandleCity => Some("BandleCity"),
             Self::Runeterra => Some("Runeterra"),
+            Self::Ryze => Some("Ryze"),
+            Self::Jhin => Some("Jhin"),
+            Self::Varus => Some("Varus"),
+            Self::Aatrox => Some("Aatrox"),
+            Self::Neeko => Some("Neeko"),
+            Self::Jax => Some("Jax"),
+            Self::Kayn => Some("Kayn"),
+            Self::PoroKing => Some("PoroKing"),
+            Self::Evelynn => Some("Evelynn"),
+            Self::Bard => Some("Bard"),
+            Self::ElderDragon => Some("ElderDragon"),
             _ => None,
         }
     }
 
-    /// Get the human friendly
+    /// Whether this [`CardRegion`] is the Origin of a [`CardRegion::Runeterra`] champion.
+    ///
+    /// See [`origins`](crate::data::deckcode::origins) for the deckbuilding rules of each Origin.
+    pub fn is_origin(&self) -> bool {
+        matches!(self,
+            Self::Ryze | Self::Jhin | Self::Varus | Self::Aatrox | Self::Neeko | Self::Jax |
+            Self::Kayn | Self::PoroKing | Self::Evelynn | Self::Bard | Self::ElderDragon
+        )
+    }
 
     /// Get the Discord emoji code associated with this [`CardRegion`].
     pub fn discord_emoji(&self) -> &'static str {
@@ -167,49 +165,58 @@ impl CardRegion {
     }
 }
 
-/// Get the [`CardRegion`] from its internal id.
+impl From<&CardRegion> for &'static str {
+    fn from(region: &CardRegion) -> Self {
+        match region {
+            CardRegion::Noxus => "Noxus",
+            CardRegion::Demacia => "Demacia",
+            CardRegion::Freljord => "Freljord",
+            CardRegion::ShadowIsles => "Shadow Isles",
+            CardRegion::Targon => "Targon",
+            CardRegion::Ionia => "Ionia",
+            CardRegion::Bilgewater => "Bilgewater",
+            CardRegion::Shurima => "Shurima",
+            CardRegion::PiltoverZaun => "Piltover & Zaun",
+            CardRegion::BandleCity => "Bandle City",
+            CardRegion::Runeterra => "Runeterra",
+            CardRegion::Ryze => "Ryze",
+            CardRegion::Jhin => "Jhin",
+            CardRegion::Varus => "Varus",
+            CardRegion::Aatrox => "Aatrox",
+            CardRegion::Neeko => "Neeko",
+            CardRegion::Jax => "Jax",
+            CardRegion::Kayn => "Kayn",
+            CardRegion::PoroKing => "Poro King",
+            CardRegion::Evelynn => "Evelynn",
+            CardRegion::Bard => "Bard",
+            CardRegion::ElderDragon => "Elder Dragon",
+            CardRegion::Unsupported => "Unknown",
+        }
+    }
+}
+
+/// Get the [`CardRegion`] from its internal id, using the process-wide [`CardIdRegistry`](crate::data::deckcode::registry::CardIdRegistry).
 ///
 /// If no region has the specified id, this will return [`CardRegion::Unsupported`].
 impl From<u32> for CardRegion {
     fn from(value: u32) -> Self {
-        match value {
-            0 => CardRegion::Demacia,
-            1 => CardRegion::Freljord,
-            2 => CardRegion::Ionia,
-            3 => CardRegion::Noxus,
-            4 => CardRegion::PiltoverZaun,
-            5 => CardRegion::ShadowIsles,
-            6 => CardRegion::Bilgewater,
-            7 => CardRegion::Shurima,
-            9 => CardRegion::Targon,
-            10 => CardRegion::BandleCity,
-            12 => CardRegion::Runeterra,
-            _ => CardRegion::Unsupported,
-        }
+        registry()
+            .region_by_id(value)
+            .map_or(CardRegion::Unsupported, |r| r.region)
     }
 }
 
-/// Get the internal id of this [`CardRegion`].
+/// Get the internal id of this [`CardRegion`], using the process-wide [`CardIdRegistry`](crate::data::deckcode::registry::CardIdRegistry).
 ///
 /// If the region has no internal id, it will return [`Err`].
 impl TryFrom<CardRegion> for u32 {
     type Error = ();
 
     fn try_from(value: CardRegion) -> Result<Self, Self::Error> {
-        match value {
-            CardRegion::Demacia => Ok(0),
-            CardRegion::Freljord => Ok(1),
-            CardRegion::Ionia => Ok(2),
-            CardRegion::Noxus => Ok(3),
-            CardRegion::PiltoverZaun => Ok(4),
-            CardRegion::ShadowIsles => Ok(5),
-            CardRegion::Bilgewater => Ok(6),
-            CardRegion::Shurima => Ok(7),
-            CardRegion::Targon => Ok(9),
-            CardRegion::BandleCity => Ok(10),
-            CardRegion::Runeterra => Ok(12),
-            _ => Err(()),
-        }
+        registry()
+            .region_by_variant(value)
+            .map(|r| r.id)
+            .ok_or(())
     }
 }
 
@@ -1,6 +1,7 @@
 //! Module defining [CardSet].
 
 use crate::data::corebundle::set::{LocalizedCardSet, LocalizedCardSetIndex};
+use crate::data::deckcode::registry::registry;
 
 /// The release set a [Card](super::card::Card) may belong to.
 ///
@@ -64,47 +65,35 @@ pub enum CardSet {
 impl CardSet {
     /// Get the [LocalizedCardSet] associated with this [CardSet].
     ///
-    /// Returns [Option::None] if no matching [LocalizedCardSet] was found, for example for [CardSet::Unsupported] sets.
+    /// Returns [Option::None] if no matching [LocalizedCardSet] was found, for example for [CardSet::Unsupported] sets: use [Card::localized_set](super::card::Card::localized_set) to find those too.
     ///
     /// Equivalent to calling [LocalizedCardSetIndex::get].
     pub fn localized<'hm>(&self, hm: &'hm LocalizedCardSetIndex) -> Option<&'hm LocalizedCardSet> {
         hm.get(self)
     }
 
-    /// Get the [`CardSet`] from its short code.
+    /// Get the [`CardSet`] from its short code, using the process-wide [`CardIdRegistry`](crate::data::deckcode::registry::CardIdRegistry).
     ///
-    /// [`CardSet::Worldwalker`] and [`CardSet::TheDarkinSaga`] share the same code `06`, so a variant cannot be determined.
+    /// [`CardSet::Worldwalker`] and [`CardSet::TheDarkinSaga`] share the same code `06`, so the first registered variant is returned.
     /// 
     /// [`CardSet::Events`] cards have the short code of the set they were released in, so it is impossible to determine if a card belongs to that set from its short code.
+    ///
+    /// If no set has the specified short code, this will return [`CardSet::Unsupported`].
     pub fn from_code(value: &str) -> Self {
-        match value {
-            "01" => Self::Foundations,
-            "02" => Self::RisingTides,
-            "03" => Self::CallOfTheMountain,
-            "04" => Self::EmpiresOfTheAscended,
-            "05" => Self::BeyondTheBandlewood,
-
-            _ => Self::Unsupported,
-        }
+        registry()
+            .set_by_code(value)
+            .map_or(Self::Unsupported, |s| s.set)
     }
 
-    /// Get the short code of this [`CardSet`].
+    /// Get the short code of this [`CardSet`], using the process-wide [`CardIdRegistry`](crate::data::deckcode::registry::CardIdRegistry).
     ///
     /// [`CardSet::Events`] cards have the short code of the set they were released in, so this method will return [`Option::None`] for them.
     ///
     /// If the set has no short code, it will also return [`Option::None`].
     pub fn to_code(&self) -> Option<String> {
-        match self {
-            Self::Foundations => Some("01".to_string()),
-            Self::RisingTides => Some("02".to_string()),
-            Self::CallOfTheMountain => Some("03".to_string()),
-            Self::EmpiresOfTheAscended => Some("04".to_string()),
-            Self::BeyondTheBandlewood => Some("05".to_string()),
-            Self::Worldwalker => Some("06".to_string()),
-            Self::TheDarkinSaga => Some("06".to_string()),
-
-            _ => None,
-        }
+        registry()
+            .set_by_variant(*self)
+            .map(|s| s.code.clone())
     }
 
     /// Get the Discord emoji code associated with this [`CardSet`].
@@ -127,41 +116,30 @@ impl CardSet {
     }
 }
 
-/// Get the [`CardSet`] from its internal id.
+/// Get the [`CardSet`] from its internal id, using the process-wide [`CardIdRegistry`](crate::data::deckcode::registry::CardIdRegistry).
 ///
-/// [`CardSet::Worldwalker`] and [`CardSet::TheDarkinSaga`] share the same id, so a variant cannot be determined.
+/// [`CardSet::Worldwalker`] and [`CardSet::TheDarkinSaga`] share the same id, so the first registered variant is returned.
 /// 
 /// [`CardSet::Events`] cards have the id of the set they were released in, so it is impossible to determine if a card belongs to that set from its id.
 impl From<u32> for CardSet {
     fn from(value: u32) -> Self {
-        match value {
-            1 => CardSet::Foundations,
-            2 => CardSet::RisingTides,
-            3 => CardSet::CallOfTheMountain,
-            4 => CardSet::EmpiresOfTheAscended,
-            5 => CardSet::BeyondTheBandlewood,
-            _ => CardSet::Unsupported,
-        }
+        registry()
+            .set_by_id(value)
+            .map_or(CardSet::Unsupported, |s| s.set)
     }
 }
 
-/// Get the internal id of this [`CardSet`].
+/// Get the internal id of this [`CardSet`], using the process-wide [`CardIdRegistry`](crate::data::deckcode::registry::CardIdRegistry).
 ///
 /// If the set has no associated internal id, it will return [`Result::Err`].
 impl TryFrom<CardSet> for u32 {
     type Error = ();
 
     fn try_from(value: CardSet) -> Result<Self, Self::Error> {
-        match value {
-            CardSet::Foundations => Ok(1),
-            CardSet::RisingTides => Ok(2),
-            CardSet::CallOfTheMountain => Ok(3),
-            CardSet::EmpiresOfTheAscended => Ok(4),
-            CardSet::BeyondTheBandlewood => Ok(5),
-            CardSet::Worldwalker => Ok(6),
-            CardSet::TheDarkinSaga => Ok(6),
-            _ => Err(()),
-        }
+        registry()
+            .set_by_variant(value)
+            .map(|s| s.id)
+            .ok_or(())
     }
 }
 
@@ -9,11 +9,17 @@ use serenity::model::prelude::*;
 use serenity::model::application::interaction::{InteractionResponseType, Interaction};
 use serenity::model::application::interaction::application_command::CommandDataOptionValue;
 use crate::data::deckcode::deck::Deck;
+use crate::data::deckcode::diff::DeckChange;
 use crate::data::deckcode::format::DeckCodeFormat;
+use crate::data::deckcode::generator::{generate_deck, GeneratorOptions};
+use crate::data::deckcode::probability::{codes_matching, DrawQuery, Mulligan};
+use crate::data::deckcode::rules::{format_registry, FormatRules};
+use crate::data::deckcode::stats::MANA_CURVE_BUCKETS;
 use crate::data::setbundle::r#type::CardType;
 use crate::data::setbundle::rarity::CardRarity;
+use crate::data::setbundle::region::CardRegion;
 use crate::data::setbundle::supertype::CardSupertype;
-use crate::search::cardsearch::CardSearchEngine;
+use crate::search::cardsearch::CardSearchEngines;
 
 /// Event handler for the bot.
 ///
@@ -39,6 +45,18 @@ Additionally, you can send me the `/deck` command together with a deck code to s
 ```
 /deck code:CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA
 ```
+You can also compare two decks with the `/diff` command, like this:
+```
+/diff before:CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA after:CQBQCBAJBUCAKCRYHKTADNIBAYBQSDQ2DQ3FEWACAECQVNQBAIBQSOK5AEAQGCIV
+```
+You can also ask me for the chance to draw some cards of a deck by a certain round with the `/draw` command, like this:
+```
+/draw code:CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA card:name:braum round:3
+```
+For a random deck challenge, use the `/random` command to generate a random legal deck, like this:
+```
+/random format:Eternal
+```
 Have a fun time playing Legends of Runeterra!
 
 _Patched Porobot isn't endorsed by Riot Games and doesn't reflect the views or opinions of Riot Games or anyone officially involved in producing or managing Riot Games properties. Riot Games, and all associated properties are trademarks or registered trademarks of Riot Games, Inc._
@@ -52,9 +70,9 @@ impl EventHandler {
     }
 
     /// Handle the `/card` command.
-    pub fn command_card<'r>(ctx: &Context, response: &'r mut EditInteractionResponse, options: HashMap<String, Option<CommandDataOptionValue>>) -> &'r mut EditInteractionResponse {
-        let typemap = ctx.data.try_read().expect("to be able to acquire read lock on CardSearchEngine");
-        let engine = typemap.get::<CardSearchEngine>().expect("CardSearchEngine to be in the TypeMap");
+    pub fn command_card<'r>(ctx: &Context, response: &'r mut EditInteractionResponse, locale: &str, options: HashMap<String, Option<CommandDataOptionValue>>) -> &'r mut EditInteractionResponse {
+        let typemap = ctx.data.try_read().expect("to be able to acquire read lock on CardSearchEngines");
+        let engine = typemap.get::<CardSearchEngines>().expect("CardSearchEngines to be in the TypeMap").for_locale(Some(locale));
 
         let query = match options.get("query") {
             Some(q) => q,
@@ -71,12 +89,12 @@ impl EventHandler {
             _ => return response.content(":warning: Invalid `query` parameter type."),
         };
 
-        let result = match engine.query(query, 1) {
+        let result = match engine.query_localized(query, 1) {
             Ok(r) => r,
             Err(_) => return response.content(":warning: Invalid card search query syntax."),
         };
 
-        let result = result.get(0);
+        let result = result.first();
 
         match result {
             Some(card) => {
@@ -94,7 +112,7 @@ impl EventHandler {
                     if !card.keywords.is_empty() {
                         e.field("Keywords", card.keywords.iter().map(|r| {
                             let icon = r.discord_emoji();
-                            let text = r.localized(&engine.globals.keywords).map_or_else(|| String::from("Unknown"), |l| l.name.clone());
+                            let text = r.localized(&engine.globals().keywords).map_or_else(|| String::from("Unknown"), |l| l.name.clone());
                             format!("{icon} {text}")
                         }).join(", "), true);
                     }
@@ -119,24 +137,21 @@ impl EventHandler {
 
                     e.field("Regions", card.regions.iter().map(|r| {
                         let icon = r.discord_emoji();
-                        let text = r.localized(&engine.globals.regions).map_or_else(|| String::from("Unknown"), |r| r.name.clone());
+                        let text = r.localized(&engine.globals().regions).map_or_else(|| String::from("Unknown"), |r| r.name.clone());
                         format!("{icon} {text}")
                     }).join(", "), false);
 
                     e.field("Set", {
                         let icon = card.set.discord_emoji();
-                        let text = card.set.localized(&engine.globals.sets).map_or_else(|| String::from("Unknown"), |r| r.name.clone());
+                        let text = card.localized_set(engine.globals()).map_or_else(|| String::from("Unknown"), |r| r.name.clone());
                         format!("{icon} {text}")
                     }, true);
 
-                    let actual_rarity = match card.supertype {
-                        CardSupertype::Champion => CardRarity::Champion,
-                        _ => card.rarity
-                    };
+                    let actual_rarity = card.crafting_rarity();
 
                     e.field("Rarity", {
                         let icon = actual_rarity.discord_emoji();
-                        let text = actual_rarity.localized(&engine.globals.rarities).map_or_else(|| String::from("Unknown"), |r| r.name.clone());
+                        let text = actual_rarity.localized(&engine.globals().rarities).map_or_else(|| String::from("Unknown"), |r| r.name.clone());
                         format!("{icon} {text}")
                     } , true);
 
@@ -163,9 +178,9 @@ impl EventHandler {
     }
 
     /// Handle the `/deck` command.
-    pub fn command_deck<'r>(ctx: &Context, response: &'r mut EditInteractionResponse, options: HashMap<String, Option<CommandDataOptionValue>>) -> &'r mut EditInteractionResponse {
-        let typemap = ctx.data.try_read().expect("to be able to acquire read lock on CardSearchEngine");
-        let engine = typemap.get::<CardSearchEngine>().expect("CardSearchEngine to be in the TypeMap");
+    pub fn command_deck<'r>(ctx: &Context, response: &'r mut EditInteractionResponse, locale: &str, options: HashMap<String, Option<CommandDataOptionValue>>) -> &'r mut EditInteractionResponse {
+        let typemap = ctx.data.try_read().expect("to be able to acquire read lock on CardSearchEngines");
+        let engine = typemap.get::<CardSearchEngines>().expect("CardSearchEngines to be in the TypeMap").for_locale(Some(locale));
 
         let code = match options.get("code") {
             Some(c) => c,
@@ -184,7 +199,7 @@ impl EventHandler {
 
         let deck = match Deck::from_code(code) {
             Ok(deck) => deck,
-            _ => return response.content(":warning: Invalid deck code."),
+            Err(e) => return response.content(format!(":warning: Invalid deck code: {}.", e)),
         };
 
         let name = match options.get("name") {
@@ -192,29 +207,43 @@ impl EventHandler {
             _ => None,
         };
 
+        let code = match deck.to_code(DeckCodeFormat::F1) {
+            Ok(code) => code,
+            Err(e) => return response.content(format!(":warning: Could not serialize the deck code: {}.", e)),
+        };
+
+        let index = engine.localized_cards(deck.contents.keys());
+        let origin_index = engine.origin_cards(deck.contents.keys());
+
         response.content(
             match name {
-                Some(name) => format!("__**{}**__\n```text\n{}\n```", name, deck.to_code(DeckCodeFormat::F1).expect("to be able to serialize the deck code")),
-                None => format!("```text\n{}\n```", deck.to_code(DeckCodeFormat::F1).expect("to be able to serialize the deck code")),
+                Some(name) => format!("__**{}**__\n```text\n{}\n```", name, code),
+                None => format!("```text\n{}\n```", code),
             });
 
-        let (format, regions) = if let Some(regions) = deck.standard(&engine.cards) {
+        let (format, regions) = if let Some(regions) = deck.standard(&origin_index) {
             ("<:standard:1095374776492638208> Standard 4.3", regions)
-        } else if let Some(regions) = deck.eternal(&engine.cards) {
+        } else if let Some(regions) = deck.eternal(&origin_index) {
             ("<:eternal:1095374779130839151> Eternal", regions)
-        } else if let Some(regions) = deck.unlimited_champions(&engine.cards) {
+        } else if let Some(regions) = deck.unlimited_champions(&origin_index) {
             ("Unlimited Champions", regions)
-        } else if let Some(regions) = deck.singleton(&engine.cards) {
+        } else if let Some(regions) = deck.singleton(&origin_index) {
             ("Singleton", regions)
         } else {
             ("Unknown", HashSet::new())
         };
 
         response.embed(|e| {
+            if name.is_none() {
+                if let Some(archetype) = deck.archetype_name(&index, &engine.globals().regions) {
+                    e.title(archetype);
+                }
+            }
+
             e.description(
                 deck.contents.iter()
                     .map(|(cc, qty)| {
-                        (cc.to_card(&engine.cards), qty)
+                        (cc.to_card(&index), qty)
                     })
                     .map(|(c, qty)| {
                         let name = match c {
@@ -228,24 +257,268 @@ impl EventHandler {
 
             e.field("Format", format, true);
 
+            let stats = deck.stats(&index);
+
+            e.field("Mana curve",
+                stats.mana_curve
+                    .iter()
+                    .enumerate()
+                    .map(|(cost, count)| match cost + 1 == MANA_CURVE_BUCKETS {
+                        true => format!("**{}+** {}", cost, count),
+                        false => format!("**{}** {}", cost, count),
+                    })
+                    .join(" · "),
+                false);
+
+            e.field("Types",
+                [CardType::Unit, CardType::Spell, CardType::Landmark, CardType::Equipment]
+                    .iter()
+                    .map(|r#type| (r#type, stats.count_of_type(*r#type)))
+                    .filter(|(_, count)| *count > 0)
+                    .map(|(r#type, count)| format!("{}× {}", count, String::from(r#type)))
+                    .join(", "),
+                true);
+
+            if let Some(average) = stats.average_cost {
+                e.field("Average cost", format!("{:.2}", average), true);
+            }
+
+            let cost = deck.crafting_cost(&index);
+
+            e.field("Crafting cost",
+                [CardRarity::Common, CardRarity::Rare, CardRarity::Epic, CardRarity::Champion]
+                    .iter()
+                    .map(|rarity| (rarity, cost.wildcards_of(*rarity)))
+                    .filter(|(_, count)| *count > 0)
+                    .map(|(rarity, count)| format!("{} {}", rarity.discord_emoji(), count))
+                    .chain(std::iter::once(format!("({} shards)", cost.shards)))
+                    .join(" "),
+                false);
+
+            if regions.is_empty() {
+                let problems = deck.check_eternal(&origin_index).violations;
+
+                if !problems.is_empty() {
+                    e.field("Not legal in Eternal, as the deck",
+                        problems
+                            .iter()
+                            .map(|problem| format!("- {}", problem))
+                            .join("\n"),
+                        false);
+                }
+            }
+
+            let display_region = |region: &CardRegion| format!(
+                "{} {}",
+                region.discord_emoji(),
+                region.localized(&engine.globals().regions)
+                    .map_or_else(|| String::from("Missing translation"), |l| l.name.clone())
+            );
+
             if !regions.is_empty() {
                 e.field("Regions",
                     regions
                         .iter()
-                        .map(|region| format!(
-                            "{} {}",
-                            region.discord_emoji(),
-                            region.localized(&engine.globals.regions)
-                                .map_or_else(|| String::from("Missing translation"), |l| l.name.clone())
-                        ))
+                        .sorted()
+                        .map(display_region)
                         .join(", "),
                     false);
+
+                let alternatives = deck.region_assignment(&origin_index, regions.len())
+                    .solutions
+                    .into_iter()
+                    .filter(|solution| *solution != regions)
+                    .map(|solution| solution.iter().sorted().map(display_region).join(", "))
+                    .join("\n");
+
+                if !alternatives.is_empty() {
+                    e.field("Regions are ambiguous, the deck could also be", alternatives, false);
+                }
             }
 
             e
         })
     }
 
+    /// Handle the `/draw` command.
+    pub fn command_draw<'r>(ctx: &Context, response: &'r mut EditInteractionResponse, locale: &str, options: HashMap<String, Option<CommandDataOptionValue>>) -> &'r mut EditInteractionResponse {
+        let typemap = ctx.data.try_read().expect("to be able to acquire read lock on CardSearchEngines");
+        let engine = typemap.get::<CardSearchEngines>().expect("CardSearchEngines to be in the TypeMap").for_locale(Some(locale));
+
+        let code = match options.get("code") {
+            Some(Some(CommandDataOptionValue::String(c))) => c,
+            Some(_) => return response.content(":warning: Invalid `code` parameter."),
+            None => return response.content(":warning: Missing `code` parameter."),
+        };
+
+        let query = match options.get("card") {
+            Some(Some(CommandDataOptionValue::String(q))) => q,
+            Some(_) => return response.content(":warning: Invalid `card` parameter."),
+            None => return response.content(":warning: Missing `card` parameter."),
+        };
+
+        let round = match options.get("round") {
+            Some(Some(CommandDataOptionValue::Integer(r))) if (1..=20).contains(r) => *r as u32,
+            Some(_) => return response.content(":warning: The `round` parameter must be between 1 and 20."),
+            None => 3,
+        };
+
+        let deck = match Deck::from_code(code) {
+            Ok(deck) => deck,
+            Err(e) => return response.content(format!(":warning: Invalid deck code: {}.", e)),
+        };
+
+        // Every card in the database may match, so the results are not limited to the top ones
+        let results = match engine.query(query, engine.shared_cards().len().max(1)) {
+            Ok(r) => r,
+            Err(_) => return response.content(":warning: Invalid card search query syntax."),
+        };
+
+        let index = engine.localized_cards(deck.contents.keys());
+
+        let targets = codes_matching(&deck, &index, |card| results.iter().any(|r| r.code == card.code));
+        if targets.is_empty() {
+            return response.content(":warning: No cards of the deck match the query.");
+        }
+
+        let copies: u32 = targets.iter().map(|t| deck.copies_of(t)).sum();
+        let names = targets
+            .iter()
+            .sorted()
+            .filter_map(|t| t.to_card(&index))
+            .map(|c| c.name.clone())
+            .join(", ");
+
+        let keep_all = DrawQuery::any_of(targets.clone(), 0);
+        let mulligan = DrawQuery {
+            mulligan: Mulligan::KeepOnly(targets),
+            ..keep_all.clone()
+        };
+
+        response.content(format!("Chance to draw at least one of **{}** ({} copies):", names, copies));
+        response.embed(|e| {
+            e.field("Round", (1..=round).map(|r| r.to_string()).join("\n"), true);
+            e.field("Keeping the hand",
+                (1..=round)
+                    .map(|r| deck.draw_probability(&DrawQuery { round: r, ..keep_all.clone() }))
+                    .map(|p| format!("{:.1}%", p * 100.0))
+                    .join("\n"),
+                true);
+            e.field("Mulliganing for it",
+                (1..=round)
+                    .map(|r| deck.draw_probability(&DrawQuery { round: r, ..mulligan.clone() }))
+                    .map(|p| format!("{:.1}%", p * 100.0))
+                    .join("\n"),
+                true);
+            e
+        });
+        response
+    }
+
+    /// Handle the `/diff` command.
+    pub fn command_diff<'r>(ctx: &Context, response: &'r mut EditInteractionResponse, locale: &str, options: HashMap<String, Option<CommandDataOptionValue>>) -> &'r mut EditInteractionResponse {
+        let typemap = ctx.data.try_read().expect("to be able to acquire read lock on CardSearchEngines");
+        let engine = typemap.get::<CardSearchEngines>().expect("CardSearchEngines to be in the TypeMap").for_locale(Some(locale));
+
+        let before = match options.get("before") {
+            Some(Some(CommandDataOptionValue::String(c))) => c,
+            Some(_) => return response.content(":warning: Invalid `before` parameter."),
+            None => return response.content(":warning: Missing `before` parameter."),
+        };
+
+        let after = match options.get("after") {
+            Some(Some(CommandDataOptionValue::String(c))) => c,
+            Some(_) => return response.content(":warning: Invalid `after` parameter."),
+            None => return response.content(":warning: Missing `after` parameter."),
+        };
+
+        let before = match Deck::from_code(before) {
+            Ok(deck) => deck,
+            Err(e) => return response.content(format!(":warning: Invalid `before` deck code: {}.", e)),
+        };
+
+        let after = match Deck::from_code(after) {
+            Ok(deck) => deck,
+            Err(e) => return response.content(format!(":warning: Invalid `after` deck code: {}.", e)),
+        };
+
+        let diff = before.diff(&after);
+        let index = engine.localized_cards(before.contents.keys().chain(after.contents.keys()));
+
+        if diff.is_empty() {
+            return response.content("The decks are identical.");
+        }
+
+        let display_changes = |changes: Vec<&DeckChange>| changes
+            .into_iter()
+            .map(|change| {
+                let name = change.card
+                    .to_card(&index)
+                    .map_or_else(|| String::from("<:invaliddeck:1056022952396730438> Unknown card"), |c| c.name.clone());
+                match change.delta() > 0 {
+                    true => format!("**+{}** {}", change.delta(), name),
+                    false => format!("**−{}** {}", -change.delta(), name),
+                }
+            })
+            .join("\n");
+
+        response.content(format!("```text\n{}\n```", diff.display(&index)));
+        response.embed(|e| {
+            let removed = diff.changes.iter().filter(|change| change.delta() < 0).collect_vec();
+            let added = diff.changes.iter().filter(|change| change.delta() > 0).collect_vec();
+
+            if !removed.is_empty() {
+                e.field("Removed", display_changes(removed), true);
+            }
+            if !added.is_empty() {
+                e.field("Added", display_changes(added), true);
+            }
+            e
+        });
+        response
+    }
+
+    /// Handle the `/random` command.
+    pub fn command_random<'r>(ctx: &Context, response: &'r mut EditInteractionResponse, locale: &str, options: HashMap<String, Option<CommandDataOptionValue>>) -> &'r mut EditInteractionResponse {
+        let rules = match options.get("format") {
+            Some(Some(CommandDataOptionValue::String(f))) => match format_registry().by_name(f) {
+                Some(rules) => rules.clone(),
+                None => return response.content(format!(":warning: Unknown format `{}`.", f)),
+            },
+            Some(_) => return response.content(":warning: Invalid `format` parameter."),
+            None => FormatRules::eternal(),
+        };
+
+        let seed = match options.get("seed") {
+            Some(Some(CommandDataOptionValue::Integer(s))) => Some(*s as u64),
+            Some(_) => return response.content(":warning: Invalid `seed` parameter."),
+            None => None,
+        };
+
+        let generated = {
+            let typemap = ctx.data.try_read().expect("to be able to acquire read lock on CardSearchEngines");
+            let engine = typemap.get::<CardSearchEngines>().expect("CardSearchEngines to be in the TypeMap").for_locale(Some(locale));
+
+            generate_deck(engine.shared_cards(), &GeneratorOptions { seed, ..GeneratorOptions::for_format(rules.clone()) })
+        };
+
+        let generated = match generated {
+            Ok(generated) => generated,
+            Err(e) => return response.content(format!(":warning: Could not generate a deck: {}.", e)),
+        };
+
+        let code = match generated.deck.to_code(DeckCodeFormat::F1) {
+            Ok(code) => code,
+            Err(e) => return response.content(format!(":warning: Could not serialize the deck code: {}.", e)),
+        };
+
+        let mut options = HashMap::new();
+        options.insert("code".to_string(), Some(CommandDataOptionValue::String(code)));
+        options.insert("name".to_string(), Some(CommandDataOptionValue::String(format!("Random {} deck (seed {})", rules.name, generated.seed))));
+
+        Self::command_deck(ctx, response, locale, options)
+    }
+
     /// Register the Slash Commands supported by this bot.
     ///
     /// If `SERENITY_DEV_GUILD_ID` is set, register them as guild commands to avoid caching, otherwise, register them as global commands.
@@ -281,6 +554,63 @@ impl EventHandler {
                         .required(false)
                     )
                 ).await?;
+                guild.create_application_command(&ctx.http, |c| c
+                    .name("diff")
+                    .description("Compare two decks, sending the cards added and removed in the chat.")
+                    .create_option(|o| o
+                        .kind(command::CommandOptionType::String)
+                        .name("before")
+                        .description("The code of the original deck.")
+                        .required(true)
+                    )
+                    .create_option(|o| o
+                        .kind(command::CommandOptionType::String)
+                        .name("after")
+                        .description("The code of the changed deck.")
+                        .required(true)
+                    )
+                ).await?;
+                guild.create_application_command(&ctx.http, |c| c
+                    .name("draw")
+                    .description("Calculate the chance to draw some cards of a deck.")
+                    .create_option(|o| o
+                        .kind(command::CommandOptionType::String)
+                        .name("code")
+                        .description("The code of the deck to draw from.")
+                        .required(true)
+                    )
+                    .create_option(|o| o
+                        .kind(command::CommandOptionType::String)
+                        .name("card")
+                        .description("The query matching the cards to draw.")
+                        .required(true)
+                    )
+                    .create_option(|o| o
+                        .kind(command::CommandOptionType::Integer)
+                        .name("round")
+                        .description("The last round to calculate the chance for; defaults to 3.")
+                        .min_int_value(1)
+                        .max_int_value(20)
+                        .required(false)
+                    )
+                ).await?;
+                guild.create_application_command(&ctx.http, |c| c
+                    .name("random")
+                    .description("Generate a random legal deck and send it in the chat.")
+                    .create_option(|o| o
+                        .kind(command::CommandOptionType::String)
+                        .name("format")
+                        .description("The name of the format the deck should be legal in; defaults to Eternal.")
+                        .required(false)
+                    )
+                    .create_option(|o| o
+                        .kind(command::CommandOptionType::Integer)
+                        .name("seed")
+                        .description("The seed of the generation, to generate the same deck again.")
+                        .min_int_value(0)
+                        .required(false)
+                    )
+                ).await?;
                 guild.create_application_command(&ctx.http, |c| c
                     .name("help")
                     .description("View the help message.")
@@ -313,6 +643,63 @@ impl EventHandler {
                         .required(false)
                     )
                 ).await?;
+                command::Command::create_global_application_command(&ctx.http, |c| c
+                    .name("diff")
+                    .description("Compare two decks, sending the cards added and removed in the chat.")
+                    .create_option(|o| o
+                        .kind(command::CommandOptionType::String)
+                        .name("before")
+                        .description("The code of the original deck.")
+                        .required(true)
+                    )
+                    .create_option(|o| o
+                        .kind(command::CommandOptionType::String)
+                        .name("after")
+                        .description("The code of the changed deck.")
+                        .required(true)
+                    )
+                ).await?;
+                command::Command::create_global_application_command(&ctx.http, |c| c
+                    .name("draw")
+                    .description("Calculate the chance to draw some cards of a deck.")
+                    .create_option(|o| o
+                        .kind(command::CommandOptionType::String)
+                        .name("code")
+                        .description("The code of the deck to draw from.")
+                        .required(true)
+                    )
+                    .create_option(|o| o
+                        .kind(command::CommandOptionType::String)
+                        .name("card")
+                        .description("The query matching the cards to draw.")
+                        .required(true)
+                    )
+                    .create_option(|o| o
+                        .kind(command::CommandOptionType::Integer)
+                        .name("round")
+                        .description("The last round to calculate the chance for; defaults to 3.")
+                        .min_int_value(1)
+                        .max_int_value(20)
+                        .required(false)
+                    )
+                ).await?;
+                command::Command::create_global_application_command(&ctx.http, |c| c
+                    .name("random")
+                    .description("Generate a random legal deck and send it in the chat.")
+                    .create_option(|o| o
+                        .kind(command::CommandOptionType::String)
+                        .name("format")
+                        .description("The name of the format the deck should be legal in; defaults to Eternal.")
+                        .required(false)
+                    )
+                    .create_option(|o| o
+                        .kind(command::CommandOptionType::Integer)
+                        .name("seed")
+                        .description("The seed of the generation, to generate the same deck again.")
+                        .min_int_value(0)
+                        .required(false)
+                    )
+                ).await?;
                 command::Command::create_global_application_command(&ctx.http, |c| c
                     .name("help")
                     .description("View the help message.")
@@ -357,9 +744,12 @@ impl serenity::client::EventHandler for EventHandler {
                 command.edit_original_interaction_response(
                     &ctx.http,
                     |response| match cmd_name {
-                        "card" => Self::command_card(&ctx, response, cmd_opts),
-                        "deck" => Self::command_deck(&ctx, response, cmd_opts),
+                        "card" => Self::command_card(&ctx, response, &command.locale, cmd_opts),
+                        "deck" => Self::command_deck(&ctx, response, &command.locale, cmd_opts),
+                        "diff" => Self::command_diff(&ctx, response, &command.locale, cmd_opts),
+                        "draw" => Self::command_draw(&ctx, response, &command.locale, cmd_opts),
                         "help" => Self::command_help(response),
+                        "random" => Self::command_random(&ctx, response, &command.locale, cmd_opts),
                         _ => response.content(":warning: Unknown command."),
                     }
                 ).await.expect("to be able to update the deferred response");
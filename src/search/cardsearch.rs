@@ -1,5 +1,9 @@
 //! Module defining a search engine to find [Card]s.
 
+use crate::data::database::{match_locale, normalize_locale, CardDatabase, MultiLocaleDatabase};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
 use crate::data::corebundle::globals::LocalizedGlobalsIndexes;
 use crate::data::setbundle::card::{Card, CardIndex};
 use crate::data::setbundle::code::CardCode;
@@ -14,9 +18,15 @@ use crate::data::setbundle::supertype::CardSupertype;
 
 /// The search engine.
 ///
-/// To create a new engine, use [CardSearchEngine::new].
+/// To create a new engine, use [CardSearchEngine::from_database].
 ///
-/// A separate search engine should be created for every locale.
+/// A separate search engine should be created for every locale; [CardSearchEngines] does so for all the locales of a [MultiLocaleDatabase].
+///
+/// # Breaking changes in 2.0.0
+///
+/// Since cards are shared between the engines of all locales, the engine does not own its cards and globals anymore: the former `globals` and `cards` fields were removed, and [CardSearchEngine::globals], [CardSearchEngine::shared_cards], [CardSearchEngine::card] and [CardSearchEngine::localized_cards] should be used instead.
+///
+/// [CardSearchEngine::query] still returns the cards of the default locale; use [CardSearchEngine::query_localized] to get them in the locale of the search engine.
 pub struct CardSearchEngine {
     /// The index of the search engine.
     index: Index,
@@ -27,11 +37,11 @@ pub struct CardSearchEngine {
     /// Struct to parse queries input by the user.
     parser: QueryParser,
 
-    /// Localization of game globals used by the search engine.
-    pub globals: LocalizedGlobalsIndexes,
+    /// The database of the cards searchable in the search engine, shared with the engines of the other locales.
+    pub database: Arc<MultiLocaleDatabase>,
 
-    /// Cards searchable in the search engine.
-    pub cards: CardIndex,
+    /// The locale of the search engine, one of the locales of the [MultiLocaleDatabase].
+    pub locale: String,
 }
 
 impl CardSearchEngine {
@@ -258,24 +268,26 @@ impl CardSearchEngine {
     ) -> Document {
         use tantivy::doc;
 
+        let set = card
+            .localized_set(globals)
+            .map(|cs| cs.name.to_owned())
+            .unwrap_or_default();
+
         doc!(
             fields.code => card.code.clone().full,
             fields.name => card.name,
             fields.r#type => String::from(&card.r#type),
-            fields.set => card.set
-                .localized(&globals.sets)
-                .map(|cs| cs.name.to_owned())
-                .unwrap_or_else(String::new),
+            fields.set => set,
             fields.rarity => card.rarity
                 .localized(&globals.rarities)
                 .map(|cr| cr.name.to_owned())
-                .unwrap_or_else(String::new),
+                .unwrap_or_default(),
             fields.collectible => u64::from(card.collectible),
             fields.regions => card.regions.iter()
                 .map(|region| region
                     .localized(&globals.regions)
                     .map(|cr| cr.name.to_owned())
-                    .unwrap_or_else(String::new)
+                    .unwrap_or_default()
                 ).join(" "),
             fields.attack => card.attack,
             fields.cost => card.cost,
@@ -283,12 +295,12 @@ impl CardSearchEngine {
             fields.spellspeed => card.spell_speed
                 .localized(&globals.spell_speeds)
                 .map(|ss| ss.name.to_owned())
-                .unwrap_or_else(String::new),
+                .unwrap_or_default(),
             fields.keywords => card.keywords.iter()
                 .map(|keyword| keyword
                     .localized(&globals.keywords)
                     .map(|ck| ck.name.to_owned())
-                    .unwrap_or_else(String::new))
+                    .unwrap_or_default())
                 .join(" "),
             fields.description => card.localized_description_text,
             fields.levelup => card.localized_levelup_text.clone(),
@@ -317,7 +329,7 @@ impl CardSearchEngine {
                 .map(|format| format
                     .localized(&globals.formats)
                     .map(|cr| cr.name.to_owned())
-                    .unwrap_or_else(String::new)
+                    .unwrap_or_default()
                 ).join(" "),
         )
     }
@@ -346,8 +358,20 @@ impl CardSearchEngine {
         parser
     }
 
-    /// Create a new [CardSearchEngine].
+    /// Create a new [CardSearchEngine] searching the given cards, whose text is assumed to be in the `en_us` locale.
+    #[deprecated(note = "use CardSearchEngine::from_database, which shares the cards with the engines of the other locales")]
     pub fn new(globals: LocalizedGlobalsIndexes, cards: CardIndex) -> Self {
+        let database = CardDatabase::new(cards, globals, "en_us".to_string(), None);
+        Self::from_database(Arc::new(MultiLocaleDatabase::new(database)), "en_us")
+    }
+
+    /// Create a new [CardSearchEngine] able to search the cards of the given [MultiLocaleDatabase] in the loaded locale best matching the given one.
+    ///
+    /// Only the search index is specific to the locale: cards are looked up in the shared database.
+    pub fn from_database(database: Arc<MultiLocaleDatabase>, locale: &str) -> Self {
+        let locale = database.resolve_locale(Some(locale)).to_string();
+        let globals = database.globals(&locale).expect("resolved locale to be loaded");
+
         let index = Self::index();
         let schema = index.schema();
         let fields = Self::schema_fields(&schema);
@@ -355,8 +379,8 @@ impl CardSearchEngine {
         index.tokenizers().register("card", Self::tokenizer());
 
         let mut writer = Self::writer(&index);
-        for card in cards.values() {
-            let document = Self::document(&fields, &globals, card.clone());
+        for card in database.default.cards.keys().filter_map(|code| database.card(code, &locale)) {
+            let document = Self::document(&fields, globals, card.into_owned());
             writer
                 .add_document(document)
                 .expect("IndexWriter threads to not panic or die before adding a document");
@@ -372,13 +396,44 @@ impl CardSearchEngine {
             index,
             reader,
             parser,
-            globals,
-            cards,
+            database,
+            locale,
         }
     }
 
-    /// Perform a query on the search engine.
-    pub fn query(&self, input: &str, top: usize) -> Result<Vec<&Card>, QueryParserError> {
+    /// The localized globals of the locale of the search engine.
+    pub fn globals(&self) -> &LocalizedGlobalsIndexes {
+        self.database.globals(&self.locale).expect("locale of the engine to be loaded")
+    }
+
+    /// The cards of the default locale, whose locale-independent data is shared by all locales.
+    ///
+    /// Use [CardSearchEngine::localized_cards] to get cards whose text is in the locale of the search engine.
+    pub fn shared_cards(&self) -> &CardIndex {
+        &self.database.default.cards
+    }
+
+    /// Get the [Card] with the given code, localized in the locale of the search engine.
+    pub fn card(&self, code: &CardCode) -> Option<Cow<'_, Card>> {
+        self.database.card(code, &self.locale)
+    }
+
+    /// Build a [CardIndex] of only the cards with the given codes, localized in the locale of the search engine.
+    ///
+    /// See [MultiLocaleDatabase::localized_cards].
+    pub fn localized_cards<'c>(&self, codes: impl IntoIterator<Item = &'c CardCode>) -> CardIndex {
+        self.database.localized_cards(codes, &self.locale)
+    }
+
+    /// Build a [CardIndex] of only the cards with the given codes, localized in the locale the Origins are checked in.
+    ///
+    /// See [MultiLocaleDatabase::origin_cards].
+    pub fn origin_cards<'c>(&self, codes: impl IntoIterator<Item = &'c CardCode>) -> CardIndex {
+        self.database.origin_cards(codes)
+    }
+
+    /// Perform a query on the search engine, returning the codes of the matching cards, in order of relevance.
+    fn query_codes(&self, input: &str, top: usize) -> Result<Vec<CardCode>, QueryParserError> {
         let searcher = self.reader.searcher();
 
         let query = self.parser.parse_query(input)?;
@@ -398,7 +453,33 @@ impl CardSearchEngine {
             .filter_map(|(_score, address)| searcher.doc(address.to_owned()).ok())
             .filter_map(|doc| doc.get_first(f_code).cloned())
             .filter_map(|field| field.as_text().map(String::from))
-            .filter_map(|code| self.cards.get(&CardCode::from(code)))
+            .map(CardCode::from)
+            .collect_vec();
+
+        Ok(results)
+    }
+
+    /// Perform a query on the search engine.
+    ///
+    /// The query is matched against the text of the locale of the search engine, but the cards are returned from the [shared cards](CardSearchEngine::shared_cards) of the default locale; use [CardSearchEngine::query_localized] to get them in the locale of the search engine.
+    pub fn query(&self, input: &str, top: usize) -> Result<Vec<&Card>, QueryParserError> {
+        let results = self
+            .query_codes(input, top)?
+            .iter()
+            .filter_map(|code| self.shared_cards().get(code))
+            .collect_vec();
+
+        Ok(results)
+    }
+
+    /// Perform a query on the search engine, returning the cards localized in the locale of the search engine.
+    ///
+    /// Cards are returned as [Cow]s, as they are borrowed from the shared database in the default locale, and localized copies in the other ones.
+    pub fn query_localized(&self, input: &str, top: usize) -> Result<Vec<Cow<'_, Card>>, QueryParserError> {
+        let results = self
+            .query_codes(input, top)?
+            .iter()
+            .filter_map(|code| self.card(code))
             .collect_vec();
 
         Ok(results)
@@ -450,7 +531,98 @@ struct CardSchemaFields {
     pub formats: Field,
 }
 
+/// A [CardSearchEngine] for every locale of a [MultiLocaleDatabase].
+pub struct CardSearchEngines {
+    /// The locale whose engine is used when the requested one is not available.
+    default_locale: String,
+
+    /// The engines, indexed by locale.
+    engines: HashMap<String, CardSearchEngine>,
+}
+
+impl CardSearchEngines {
+    /// Create a [CardSearchEngine] for every locale of the given [MultiLocaleDatabase], all sharing it.
+    pub fn new(database: Arc<MultiLocaleDatabase>) -> Self {
+        let engines = database
+            .locales()
+            .into_iter()
+            .map(|locale| (normalize_locale(locale), CardSearchEngine::from_database(Arc::clone(&database), locale)))
+            .collect();
+
+        Self {
+            default_locale: normalize_locale(database.default_locale()),
+            engines,
+        }
+    }
+
+    /// Get the [CardSearchEngine] of the locale best matching the requested one, such as the language of an user.
+    ///
+    /// See [MultiLocaleDatabase::resolve_locale] for how locales are matched.
+    pub fn for_locale(&self, requested: Option<&str>) -> &CardSearchEngine {
+        let locales = std::iter::once(self.default_locale.as_str())
+            .chain(self.engines.keys().map(String::as_str).filter(|locale| *locale != self.default_locale).sorted());
+
+        let locale = requested
+            .and_then(|requested| match_locale(requested, locales))
+            .unwrap_or(&self.default_locale);
+
+        &self.engines[locale]
+    }
+
+    /// The locales with a [CardSearchEngine].
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.engines.keys().map(String::as_str)
+    }
+}
+
 #[cfg(feature = "discord")]
 impl serenity::prelude::TypeMapKey for CardSearchEngine {
     type Value = CardSearchEngine;
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "discord")]
+impl serenity::prelude::TypeMapKey for CardSearchEngines {
+    type Value = CardSearchEngines;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn engines_share_database() {
+        let english = CardDatabase::from_wd();
+
+        let mut italian = english.clone();
+        italian.locale = "it_it".to_string();
+        let code = CardCode::from("01FR009".to_string());
+        italian.cards.get_mut(&code).unwrap().name = "Braumissimo".to_string();
+
+        let mut database = MultiLocaleDatabase::new(english);
+        database.add_locale(italian);
+        let database = Arc::new(database);
+
+        let engines = CardSearchEngines::new(Arc::clone(&database));
+        assert_eq!(Arc::strong_count(&database), 3);
+
+        let engine = engines.for_locale(Some("it"));
+        assert_eq!(engine.locale, "it_it");
+        assert_eq!(engine.query("Braumissimo", 1).unwrap()[0].name, "Braum");
+        assert_eq!(engine.query_localized("Braumissimo", 1).unwrap()[0].name, "Braumissimo");
+        assert_eq!(engine.localized_cards([&code])[&code].name, "Braumissimo");
+
+        let engine = engines.for_locale(None);
+        assert_eq!(engine.query("Braum", 1).unwrap()[0].name, "Braum");
+        assert!(engine.query("Braumissimo", 1).unwrap().is_empty());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_constructor() {
+        let database = CardDatabase::from_wd();
+        let engine = CardSearchEngine::new(database.globals, database.cards);
+
+        assert_eq!(engine.locale, "en_us");
+        assert_eq!(engine.query("Braum", 1).unwrap()[0].name, "Braum");
+    }
+}
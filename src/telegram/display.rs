@@ -7,13 +7,13 @@ use std::collections::HashSet;
 use crate::data::corebundle::globals::LocalizedGlobalsIndexes;
 use crate::data::corebundle::keyword::LocalizedCardKeywordIndex;
 use crate::data::corebundle::region::LocalizedCardRegionIndex;
-use crate::data::corebundle::set::LocalizedCardSetIndex;
 use crate::data::deckcode::deck::Deck;
+use crate::data::deckcode::diff::DeckDiff;
+use crate::data::deckcode::stats::{DeckStats, MANA_CURVE_BUCKETS};
 use crate::data::setbundle::card::{Card, CardIndex};
 use crate::data::setbundle::keyword::CardKeyword;
 use crate::data::setbundle::r#type::CardType;
 use crate::data::setbundle::region::CardRegion;
-use crate::data::setbundle::set::CardSet;
 use crate::data::setbundle::subtype::CardSubtype;
 use crate::data::setbundle::supertype::CardSupertype;
 use itertools::Itertools;
@@ -37,7 +37,7 @@ pub fn display_card(globals: &LocalizedGlobalsIndexes, card: &Card) -> String {
 
     let body = format!("{}{}{}", &keywords, &description, &levelup);
 
-    let set = display_set(&card.set, &globals.sets);
+    let set = display_set(card, globals);
     let regions = display_regions(&card.regions, &globals.regions);
     let flavor = display_flavor(&card.main_art().expect("Card to have at least one illustration").full_png, &card.localized_flavor_text);
 
@@ -184,7 +184,7 @@ fn display_keywords(keywords: &[CardKeyword], hm: &LocalizedCardKeywordIndex) ->
 /// Render a [Card::localized_description_text] in [Telegram Bot HTML].
 ///
 /// [Telegram Bot HTML]: https://core.telegram.org/bots/api#html-style
-fn display_description(description: &String) -> String {
+fn display_description(description: &str) -> String {
     if description.is_empty() {
         "".to_string()
     } else {
@@ -195,7 +195,7 @@ fn display_description(description: &String) -> String {
 /// Render a [Card::localized_levelup_text] in [Telegram Bot HTML].
 ///
 /// [Telegram Bot HTML]: https://core.telegram.org/bots/api#html-style
-fn display_levelup(levelup: &String) -> String {
+fn display_levelup(levelup: &str) -> String {
     if levelup.is_empty() {
         "".to_string()
     } else {
@@ -203,13 +203,13 @@ fn display_levelup(levelup: &String) -> String {
     }
 }
 
-/// Render a [CardSet] in [Telegram Bot HTML].
+/// Render the [CardSet] of a [Card] in [Telegram Bot HTML].
 ///
 /// [Telegram Bot HTML]: https://core.telegram.org/bots/api#html-style
-fn display_set(set: &CardSet, hm: &LocalizedCardSetIndex) -> String {
+fn display_set(card: &Card, globals: &LocalizedGlobalsIndexes) -> String {
     format!(
         "<i>{}</i>",
-        set.localized(hm)
+        card.localized_set(globals)
             .map(|o| format!("<i>{}</i>", escape(&o.name)))
             .unwrap_or_else(|| "UNKNOWN?".to_string())
     )
@@ -245,8 +245,10 @@ fn display_flavor(art_url: &str, localized_flavor_text: &str) -> String {
 
 /// Render a [Deck] in [Telegram Bot HTML], with an optional `name`.
 ///
+/// Legality is checked with the cards of `origin_index`, which should be localized in the [ORIGINS_LOCALE](crate::data::deckcode::origins::ORIGINS_LOCALE).
+///
 /// [Telegram Bot HTML]: https://core.telegram.org/bots/api#html-style
-pub fn display_deck(index: &CardIndex, deck: &Deck, code: &str, name: &Option<&str>) -> String {
+pub fn display_deck(index: &CardIndex, origin_index: &CardIndex, deck: &Deck, code: &str, name: &Option<&str>) -> String {
     let cards = deck
         .contents
         .keys()
@@ -288,38 +290,112 @@ pub fn display_deck(index: &CardIndex, deck: &Deck, code: &str, name: &Option<&s
 
     let mut tags: Vec<String> = vec![];
 
-    let regions = if let Some(regions) = deck.standard(index) {
+    let regions = if let Some(regions) = deck.standard(origin_index) {
         tags.push("#Standard_4_5".to_string());
         regions
-    } else if let Some(regions) = deck.eternal(index) {
+    } else if let Some(regions) = deck.eternal(origin_index) {
         tags.push("#Eternal".to_string());
         regions
-    } else if let Some(regions) = deck.unlimited_champions(index) {
+    } else if let Some(regions) = deck.unlimited_champions(origin_index) {
         tags.push("#UnlimitedChampions".to_string());
         regions
-    } else if let Some(regions) = deck.singleton(index) {
+    } else if let Some(regions) = deck.singleton(origin_index) {
         tags.push("#Singleton".to_string());
         regions
     } else {
         HashSet::new()
     };
 
-    for region in regions {
-        tags.push(match region {
-            CardRegion::Unsupported => "<i>Unknown</i>".to_string(),
-            _ => format!("#{}", region.to_tag().map_or_else(|| "<i>Unknown</i>", |r| r)),
-        })
+    let problems = match tags.is_empty() {
+        true => deck.check_eternal(origin_index).violations,
+        false => vec![],
+    };
+
+    let assignment = deck.region_assignment(origin_index, regions.len());
+    let alternatives = assignment
+        .solutions
+        .iter()
+        .filter(|solution| **solution != regions)
+        .map(|solution| solution.iter().sorted().map(display_region_tag).join(", "))
+        .join(" or ");
+
+    for region in regions.iter().sorted() {
+        tags.push(display_region_tag(region))
     }
 
     let tags = tags.join(", ");
     let tags = if !tags.is_empty() { format!("{}\n", &tags) } else { "".to_string() };
+    let tags = if !alternatives.is_empty() { format!("{}<i>Regions are ambiguous, the deck could also be</i> {}\n", &tags, &alternatives) } else { tags };
+
+    let problems = problems
+        .iter()
+        .map(|problem| format!("<i>{}</i>", escape(&problem.to_string())))
+        .join("\n");
+    let tags = if !problems.is_empty() { format!("{}Not legal in Eternal, as the deck:\n{}\n", &tags, &problems) } else { tags };
+
+    let stats = display_deck_stats(&deck.stats(index));
+    let stats = format!("{}\n<i>Crafting cost:</i> {} shards", &stats, deck.crafting_cost(index).shards);
 
     match name {
-        Some(name) => format!("<b><u>{}</u></b>\n<code>{}</code>\n{}\n{}", &name, &code, &tags, &cards),
-        None => format!("<code>{}</code>\n{}\n{}", &code, &tags, &cards),
+        Some(name) => format!("<b><u>{}</u></b>\n<code>{}</code>\n{}\n{}\n\n{}", &name, &code, &tags, &cards, &stats),
+        None => format!("<code>{}</code>\n{}\n{}\n\n{}", &code, &tags, &cards, &stats),
     }
 }
 
+/// Render the [`DeckDiff`] between two [`Deck`]s in [Telegram Bot HTML].
+///
+/// [Telegram Bot HTML]: https://core.telegram.org/bots/api#html-style
+pub fn display_deck_diff(index: &CardIndex, diff: &DeckDiff, before: &str, after: &str) -> String {
+    let changes = match diff.is_empty() {
+        true => "<i>The decks are identical.</i>".to_string(),
+        false => escape(&diff.display(index)),
+    };
+
+    format!("<code>{}</code>\n→ <code>{}</code>\n\n{}", &before, &after, &changes)
+}
+
+/// Render the [`DeckStats`] of a [`Deck`] in [Telegram Bot HTML].
+///
+/// [Telegram Bot HTML]: https://core.telegram.org/bots/api#html-style
+fn display_deck_stats(stats: &DeckStats) -> String {
+    let curve = stats.mana_curve
+        .iter()
+        .enumerate()
+        .map(|(cost, count)| match cost + 1 == MANA_CURVE_BUCKETS {
+            true => format!("{}+: {}", cost, count),
+            false => format!("{}: {}", cost, count),
+        })
+        .join(" | ");
+
+    let types = [
+        (CardType::Unit, "units"),
+        (CardType::Spell, "spells"),
+        (CardType::Landmark, "landmarks"),
+        (CardType::Equipment, "equipments"),
+    ]
+        .iter()
+        .map(|(r#type, name)| (stats.count_of_type(*r#type), name))
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, name)| format!("{} {}", count, name))
+        .join(", ");
+
+    let average = match stats.average_cost {
+        Some(average) => format!("{:.2}", average),
+        None => "-".to_string(),
+    };
+
+    format!("<i>Mana curve:</i> {}\n<i>Types:</i> {}\n<i>Average cost:</i> {}", &curve, &types, &average)
+}
+
+/// Render a [`CardRegion`] as a hashtag in [Telegram Bot HTML].
+///
+/// [Telegram Bot HTML]: https://core.telegram.org/bots/api#html-style
+fn display_region_tag(region: &CardRegion) -> String {
+    match region {
+        CardRegion::Unsupported => "<i>Unknown</i>".to_string(),
+        _ => format!("#{}", region.to_tag().unwrap_or("<i>Unknown</i>")),
+    }
+}
 
 // https://stackoverflow.com/a/38406885/4334568
 fn titlecase(s: &str) -> String {
@@ -1,8 +1,9 @@
 //! Module providing handlers for @patchedporobot on Telegram.
 
 use crate::data::deckcode::deck::Deck;
-use crate::search::cardsearch::CardSearchEngine;
-use crate::telegram::inline::{card_to_inlinequeryresult, deck_to_inlinequeryresult};
+use crate::data::deckcode::extract::extract_decks;
+use crate::search::cardsearch::CardSearchEngines;
+use crate::telegram::inline::{card_to_inlinequeryresult, deck_diff_to_inlinequeryresult, deck_to_inlinequeryresult};
 use itertools::Itertools;
 use log::*;
 use teloxide::dispatching::DpHandlerDescription;
@@ -13,15 +14,17 @@ use teloxide::types::{ParseMode, Recipient};
 use lazy_static::lazy_static;
 use regex::Regex;
 
-/// Handle inline queries by searching cards on the [CardSearchEngine].
+/// Handle inline queries by searching cards on the [CardSearchEngine](crate::search::cardsearch::CardSearchEngine) of the language of the user.
 #[allow(clippy::never_loop)]
 pub fn inline_query_handler(
     crystal: String,
-    engine: CardSearchEngine,
+    engines: CardSearchEngines,
 ) -> Handler<'static, DependencyMap, ResponseResult<()>, DpHandlerDescription> {
     Update::filter_inline_query().chain(dptree::endpoint(move |query: InlineQuery, bot: Bot| {
         info!("Handling inline query...");
 
+        let engine = engines.for_locale(query.from.language_code.as_deref());
+
         // It's not a real loop, it's just to make the code flow more tolerable.
         let payload: AnswerInlineQuery = loop {
             if query.query.is_empty() {
@@ -39,18 +42,22 @@ pub fn inline_query_handler(
 
             lazy_static! {
                 static ref DECK_RE: Regex = Regex::new(r#"^(?P<code>[ABCDEFGHIJKLMNOPQRSTUVWXYZ234567]+)(?:\s+(?P<name>.+?))?\s*$"#).unwrap();
+                static ref DIFF_RE: Regex = Regex::new(r#"^(?P<before>[ABCDEFGHIJKLMNOPQRSTUVWXYZ234567]+)\s+(?P<after>[ABCDEFGHIJKLMNOPQRSTUVWXYZ234567]+)\s*$"#).unwrap();
             }
-            
-            if let Some(deck_captures) = DECK_RE.captures(&query.query) {
-                if let Some(deck_code) = deck_captures.name("code") {
-                    if let Ok(deck) = Deck::from_code(deck_code.as_str()) {
-                        
-                        debug!("Parsed deck successfully!");
-                        let name = deck_captures.name("name").map(|m| m.as_str());
 
+            if let Some(diff_captures) = DIFF_RE.captures(&query.query) {
+                let before = Deck::from_code(&diff_captures["before"]);
+                let after = Deck::from_code(&diff_captures["after"]);
+
+                if let (Ok(before), Ok(after)) = (before, after) {
+                    debug!("Parsed two decks successfully!");
+
+                    let index = engine.localized_cards(before.contents.keys().chain(after.contents.keys()));
+
+                    if let Ok(result) = deck_diff_to_inlinequeryresult(&crystal, &index, &before, &after) {
                         break AnswerInlineQuery {
                             inline_query_id: query.id.clone(),
-                            results: vec![deck_to_inlinequeryresult(&crystal, &engine.cards, &deck, &name)],
+                            results: vec![result],
                             cache_time: None,
                             is_personal: Some(false),
                             next_offset: None,
@@ -60,9 +67,54 @@ pub fn inline_query_handler(
                     }
                 }
             }
+            
+            if let Some(deck_captures) = DECK_RE.captures(&query.query) {
+                if let Some(deck_code) = deck_captures.name("code") {
+                    if let Ok(deck) = Deck::from_code(deck_code.as_str()) {
+                        
+                        debug!("Parsed deck successfully!");
+                        let name = deck_captures.name("name").map(|m| m.as_str());
+
+                        let index = engine.localized_cards(deck.contents.keys());
+                        let origin_index = engine.origin_cards(deck.contents.keys());
+
+                        if let Ok(result) = deck_to_inlinequeryresult(&crystal, engine.globals(), &index, &origin_index, &deck, &name) {
+                            break AnswerInlineQuery {
+                                inline_query_id: query.id.clone(),
+                                results: vec![result],
+                                cache_time: None,
+                                is_personal: Some(false),
+                                next_offset: None,
+                                switch_pm_text: None,
+                                switch_pm_parameter: None,
+                            };
+                        }
+                    }
+                }
+            }
+
+            let found = extract_decks(&query.query, engine.shared_cards());
+            if !found.is_empty() {
+                debug!("Found {} deck codes in the query!", found.len());
+
+                break AnswerInlineQuery {
+                    inline_query_id: query.id.clone(),
+                    results: found
+                        .iter()
+                        .unique_by(|f| &f.code)
+                        .take(50)
+                        .filter_map(|f| deck_to_inlinequeryresult(&crystal, engine.globals(), &engine.localized_cards(f.deck.contents.keys()), &engine.origin_cards(f.deck.contents.keys()), &f.deck, &None).ok())
+                        .collect_vec(),
+                    cache_time: None,
+                    is_personal: Some(false),
+                    next_offset: None,
+                    switch_pm_text: None,
+                    switch_pm_parameter: None,
+                };
+            }
 
             debug!("Querying the card search engine...");
-            let results = engine.query(&query.query, 50);
+            let results = engine.query_localized(&query.query, 50);
 
             if results.is_err() {
                 debug!("Invalid card search query syntax.");
@@ -97,7 +149,7 @@ pub fn inline_query_handler(
                 inline_query_id: query.id.clone(),
                 results: results
                     .iter()
-                    .map(|card| card_to_inlinequeryresult(&crystal, &engine.globals, card))
+                    .map(|card| card_to_inlinequeryresult(&crystal, engine.globals(), card))
                     .collect_vec(),
                 cache_time: Some(300),
                 is_personal: Some(false),
@@ -135,6 +187,9 @@ To read all details on the queries you can ask me to perform, visit the <a href=
 Additionally, you can ask me to read a deck code to send the full deck details in chat, like this:
 <pre>@patchedporobot CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA</pre>
 
+To compare two decks, send me both deck codes separated by a space, like this:
+<pre>@patchedporobot CECQCAQCA4AQIAYKAIAQGLRWAQAQECAPEUXAIAQDAEBQOCIBAIAQEMJYAA CQBQCBAJBUCAKCRYHKTADNIBAYBQSDQ2DQ3FEWACAECQVNQBAIBQSOK5AEAQGCIV</pre>
+
 Have a fun time playing Legends of Runeterra!
 
 <i>@patchedporobot isn't endorsed by Riot Games and doesn't reflect the views or opinions of Riot Games or anyone officially involved in producing or managing Riot Games properties. Riot Games, and all associated properties are trademarks or registered trademarks of Riot Games, Inc.</i>
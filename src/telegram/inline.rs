@@ -3,10 +3,11 @@
 //! [inline mode]: https://core.telegram.org/bots/api#inline-mode
 
 use crate::data::corebundle::globals::LocalizedGlobalsIndexes;
-use crate::data::deckcode::deck::Deck;
+use crate::data::deckcode::deck::{Deck, DeckEncodingResult};
+use crate::data::deckcode::diff::DeckDiff;
 use crate::data::deckcode::format::DeckCodeFormat;
 use crate::data::setbundle::card::{Card, CardIndex};
-use crate::telegram::display::{display_card, display_deck};
+use crate::telegram::display::{display_card, display_deck, display_deck_diff};
 use teloxide::types::{
     InlineQueryResult, InlineQueryResultArticle, InlineQueryResultPhoto, InputMessageContent,
     InputMessageContentText, ParseMode,
@@ -45,24 +46,31 @@ pub fn card_to_inlinequeryresult(
 }
 
 /// Convert a [Deck] with an optional name into a [InlineQueryResult].
+///
+/// If no name is given, the title of the result uses the [archetype name](Deck::archetype_name) of the deck instead.
+///
+/// Fails if the deck cannot be serialized back into a deck code.
 pub fn deck_to_inlinequeryresult(
     crystal: &str,
+    globals: &LocalizedGlobalsIndexes,
     index: &CardIndex,
+    origin_index: &CardIndex,
     deck: &Deck,
     name: &Option<&str>
-) -> InlineQueryResult {
-    let code = deck
-        .to_code(DeckCodeFormat::F1)
-        .expect("serialized deck to deserialize properly");
+) -> DeckEncodingResult<InlineQueryResult> {
+    let code = deck.to_code(DeckCodeFormat::F1)?;
 
-    InlineQueryResult::Article(InlineQueryResultArticle {
+    Ok(InlineQueryResult::Article(InlineQueryResultArticle {
         id: format!("{}:{:x}", &crystal, md5::compute(&code)),
         title: match &name {
             Some(name) => format!(r#"Deck "{}" with {} cards"#, name, deck.contents.len()),
-            None => format!("Deck with {} cards", deck.contents.len())
+            None => match deck.archetype_name(index, &globals.regions) {
+                Some(archetype) => format!("{} deck with {} cards", archetype, deck.contents.len()),
+                None => format!("Deck with {} cards", deck.contents.len()),
+            }
         },
         input_message_content: InputMessageContent::Text(InputMessageContentText {
-            message_text: display_deck(index, deck, &code, name),
+            message_text: display_deck(index, origin_index, deck, &code, name),
             parse_mode: Some(ParseMode::Html),
             entities: None,
             disable_web_page_preview: Some(true),
@@ -74,5 +82,38 @@ pub fn deck_to_inlinequeryresult(
         thumb_url: None,
         thumb_width: None,
         thumb_height: None,
-    })
+    }))
+}
+
+/// Convert the [DeckDiff] between two [Deck]s into a [InlineQueryResult].
+///
+/// Fails if either deck cannot be serialized back into a deck code.
+pub fn deck_diff_to_inlinequeryresult(
+    crystal: &str,
+    index: &CardIndex,
+    before: &Deck,
+    after: &Deck,
+) -> DeckEncodingResult<InlineQueryResult> {
+    let before_code = before.to_code(DeckCodeFormat::F1)?;
+    let after_code = after.to_code(DeckCodeFormat::F1)?;
+
+    let diff = DeckDiff::between(before, after);
+
+    Ok(InlineQueryResult::Article(InlineQueryResultArticle {
+        id: format!("{}:{:x}", &crystal, md5::compute(format!("{}:{}", &before_code, &after_code))),
+        title: format!("Difference between decks, with {} changed cards", diff.changes.len()),
+        input_message_content: InputMessageContent::Text(InputMessageContentText {
+            message_text: display_deck_diff(index, &diff, &before_code, &after_code),
+            parse_mode: Some(ParseMode::Html),
+            entities: None,
+            disable_web_page_preview: Some(true),
+        }),
+        reply_markup: None,
+        url: None,
+        hide_url: None,
+        description: None,
+        thumb_url: None,
+        thumb_width: None,
+        thumb_height: None,
+    }))
 }
@@ -1,23 +1,35 @@
 //! Module defining the [`main`] function for `patched_porobot_telegram`.
 
 use std::env;
-use crate::data::corebundle::create_globalindexes_from_dd_latest;
-use crate::data::setbundle::create_cardindex_from_dd_latest;
-use crate::search::cardsearch::CardSearchEngine;
+use std::sync::Arc;
+use crate::data::cache::BundleCache;
+use crate::data::database::{parse_locales, MultiLocaleDatabase};
+use crate::search::cardsearch::CardSearchEngines;
 use crate::telegram::handler::{inline_query_handler, message_handler};
 use log::*;
 use rand::Rng;
 use teloxide::prelude::*;
 
 /// The function that `patched_porobot_telegram` should run when it's started.
+///
+/// # Environment
+///
+/// - `DATA_DRAGON_LOCALE`: comma-separated list of the locales to load, such as `it_it,en_us`; the first one is the default locale.
+/// - `DATA_DRAGON_SET_CODES`: comma-separated list of the codes of the sets to load, such as `set1,set2`.
+/// - `DATA_DRAGON_VERSION`: the Data Dragon version to load, such as `5_9_0`; defaults to `latest`.
+/// - `DATA_DRAGON_CACHE_DIR`: optional directory to cache the downloaded bundles in.
+/// - `TELOXIDE_TOKEN`: the token of the Telegram bot.
+///
+/// The Origins of decks are always checked against the `en_us` texts of the cards: if `en_us` is not among the loaded locales, its bundles are downloaded at startup too, costing as much time and memory for card texts as loading one more locale, but it is not offered to users as a language.
 pub async fn main() {
     pretty_env_logger::init();
     debug!("Logger initialized successfully!");
 
-    debug!("Detecting locale to use...");
-    let locale = env::var("DATA_DRAGON_LOCALE")
+    debug!("Detecting locales to use...");
+    let locales: String = env::var("DATA_DRAGON_LOCALE")
         .expect("DATA_DRAGON_LOCALE to be set");
-    debug!("Using {} locale!", &locale);
+    let locales: Vec<&str> = parse_locales(&locales);
+    debug!("Using {:?} locales!", &locales);
 
     debug!("Detecting set codes to fetch...");
     let known_set_codes: String = env::var("DATA_DRAGON_SET_CODES")
@@ -27,17 +39,31 @@ pub async fn main() {
         .collect();
     debug!("Detected set codes: {:?}", &known_set_codes);
 
-    debug!("Creating LocalizedGlobalIndexes...");
-    let globals = create_globalindexes_from_dd_latest(&locale).await;
-    debug!("Created LocalizedGlobalIndexes!");
+    let cache = env::var("DATA_DRAGON_CACHE_DIR").ok().map(BundleCache::new);
+    let version = env::var("DATA_DRAGON_VERSION")
+        .unwrap_or_else(|_| "latest".to_string());
 
-    debug!("Creating CardIndex...");
-    let cards = create_cardindex_from_dd_latest(&locale, known_set_codes.into_iter()).await;
-    debug!("Created CardIndex!");
+    debug!("Creating MultiLocaleDatabase...");
+    let database = match MultiLocaleDatabase::load(&locales, &version, cache.as_ref(), &known_set_codes).await {
+        Ok(database) => database,
+        Err(error) => {
+            error!("Could not create the MultiLocaleDatabase: {}", error);
+            std::process::exit(1);
+        }
+    };
+    for failure in database.failures.iter() {
+        warn!("Starting without the cards of {}, as they could not be loaded: {}", failure.bundle, failure.error);
+    }
+    let database = database.value;
+    debug!("Created MultiLocaleDatabase with {:?} locales, and {} as default!", database.locales(), database.default_locale());
 
-    debug!("Creating CardSearchEngine...");
-    let engine = CardSearchEngine::new(globals, cards);
-    debug!("Created CardSearchEngine!");
+    debug!("Extending CardIdRegistry...");
+    database.default.register();
+    debug!("Extended CardIdRegistry!");
+
+    debug!("Creating CardSearchEngines...");
+    let engines = CardSearchEngines::new(Arc::new(database));
+    debug!("Created CardSearchEngines!");
 
     debug!("Creating Telegram bot with parameters from the environment...");
     let bot = Bot::from_env();
@@ -55,7 +81,7 @@ pub async fn main() {
 
     debug!("Creating handlers...");
     let handler = dptree::entry()
-        .branch(inline_query_handler(crystal, engine))
+        .branch(inline_query_handler(crystal, engines))
         .branch(message_handler());
     debug!("Created handlers!");
 